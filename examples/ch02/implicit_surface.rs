@@ -1,7 +1,10 @@
 use bytemuck::cast_slice;
-use cgmath::{Matrix, Matrix4, SquareMatrix};
+use cgmath::{InnerSpace, Matrix, Matrix4, SquareMatrix};
 use rand::{rngs::ThreadRng, Rng};
-use std::{collections::HashMap, iter};
+use std::{
+    collections::{HashMap, HashSet},
+    iter,
+};
 use wgpu::{util::DeviceExt, VertexBufferLayout};
 use winit::{
     event::*,
@@ -11,6 +14,14 @@ use winit::{
 use wgpu_simplified as ws;
 use wgpu_marching_cubes::{colormap, marching_cubes_table};
 
+// `Instant::now()` panics on wasm32-unknown-unknown (no
+// monotonic clock syscall to back it), so the web build swaps in
+// `web_time`'s drop-in replacement backed by `performance.now()`.
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
 fn create_color_data(colormap_name: &str) -> Vec<[f32; 4]> {
     let cdata = colormap::colormap_data(colormap_name);
     let mut data: Vec<[f32; 4]> = vec![];
@@ -20,6 +31,107 @@ fn create_color_data(colormap_name: &str) -> Vec<[f32; 4]> {
     data
 }
 
+/// Registry of every WGSL module an `#include` directive can name, keyed by
+/// the same virtual path it's included under. `include_str!` needs a literal
+/// path, so this match is the one place new shared modules get registered;
+/// `WgslPreprocessor` calls it recursively to resolve nested includes.
+fn resolve_wgsl_module(path: &str) -> Option<&'static str> {
+    match path {
+        "implicit_func.wgsl" => Some(include_str!("implicit_func.wgsl")),
+        "implicit_value.wgsl" => Some(include_str!("implicit_value.wgsl")),
+        "implicit_surface.wgsl" => Some(include_str!("implicit_surface.wgsl")),
+        "colormap.wgsl" => Some(include_str!("colormap.wgsl")),
+        "../ch01/shader_vert.wgsl" => Some(include_str!("../ch01/shader_vert.wgsl")),
+        "../ch01/shader_frag.wgsl" => Some(include_str!("../ch01/shader_frag.wgsl")),
+        _ => None,
+    }
+}
+
+/// Resolves `#include "virtual/path.wgsl"` directives (cycle-checked against
+/// `resolve_wgsl_module`) and simple `#define NAME` / `#ifdef NAME` ...
+/// `#endif` conditionals before a shader's source reaches
+/// `create_shader_module`, so colormap/noise/isosurface-math helpers can
+/// live in one file and be shared by `cs_pipelines[0]`, `cs_pipelines[1]`
+/// and the main `pipeline` instead of being pasted into each compute shader
+/// like `[cs_func_file, cs_value_file].join("\n")` used to.
+struct WgslPreprocessor<'a> {
+    resolver: &'a dyn Fn(&str) -> Option<&'static str>,
+    defines: HashSet<String>,
+}
+
+impl<'a> WgslPreprocessor<'a> {
+    fn new(resolver: &'a dyn Fn(&str) -> Option<&'static str>) -> Self {
+        Self {
+            resolver,
+            defines: HashSet::new(),
+        }
+    }
+
+    fn define(mut self, name: &str) -> Self {
+        self.defines.insert(name.to_string());
+        self
+    }
+
+    /// Concatenates `entry_path`'s resolved source with everything it
+    /// transitively `#include`s, in reference order.
+    fn process(&self, entry_path: &str) -> String {
+        let mut include_stack = Vec::new();
+        let mut defines = self.defines.clone();
+        self.process_inner(entry_path, &mut include_stack, &mut defines)
+    }
+
+    fn process_inner(
+        &self,
+        path: &str,
+        include_stack: &mut Vec<String>,
+        defines: &mut HashSet<String>,
+    ) -> String {
+        if include_stack.iter().any(|seen| seen == path) {
+            include_stack.push(path.to_string());
+            panic!("wgsl #include cycle: {}", include_stack.join(" -> "));
+        }
+        let source = (self.resolver)(path)
+            .unwrap_or_else(|| panic!("unresolved wgsl #include: \"{}\"", path));
+
+        include_stack.push(path.to_string());
+        let mut resolved = String::new();
+        let mut skip_depth = 0u32;
+        for line in source.lines() {
+            let trimmed = line.trim_start();
+            if let Some(include_path) = trimmed.strip_prefix("#include") {
+                if skip_depth == 0 {
+                    let include_path = include_path.trim().trim_matches('"');
+                    resolved.push_str(&self.process_inner(include_path, include_stack, defines));
+                    resolved.push('\n');
+                }
+                continue;
+            }
+            if let Some(name) = trimmed.strip_prefix("#define") {
+                if skip_depth == 0 {
+                    defines.insert(name.trim().to_string());
+                }
+                continue;
+            }
+            if let Some(name) = trimmed.strip_prefix("#ifdef") {
+                if skip_depth > 0 || !defines.contains(name.trim()) {
+                    skip_depth += 1;
+                }
+                continue;
+            }
+            if trimmed.starts_with("#endif") {
+                skip_depth = skip_depth.saturating_sub(1);
+                continue;
+            }
+            if skip_depth == 0 {
+                resolved.push_str(line);
+                resolved.push('\n');
+            }
+        }
+        include_stack.pop();
+        resolved
+    }
+}
+
 fn surface_type_map() -> HashMap<u32, String> {
     let mut surface_type = HashMap::new();
     surface_type.insert(0, String::from("Sphere"));
@@ -41,6 +153,244 @@ fn get_surface_type(key: u32) -> String {
     map.get(&key).map(|s| s.to_string()).unwrap_or_default()
 }
 
+const INSTANCE_GRID: u32 = 10;
+const INSTANCE_SPACING: f32 = 2.5;
+
+// Marching-cubes output has no UVs, so the alternative to colormap shading
+// is triplanar: project this image along X/Y/Z and blend by normal.
+const TRIPLANAR_TEXTURE_PATH: &str = "examples/ch02/rock.jpg";
+
+/// Whether `State::render` presents to the window's swapchain or renders
+/// into an offscreen texture and dumps each frame to disk, so a turntable
+/// animation can be produced on a CI box with no display attached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RenderTarget {
+    Window,
+    Offscreen,
+}
+
+/// How `shader_frag.wgsl`'s `sample_shadow` turns the shadow map into a
+/// visibility factor. The `u32` discriminant here is what actually reaches
+/// the shader via `shadow_params_buffer`, so the two must stay in lock-step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShadowFilterMode {
+    Off,
+    Hardware2x2,
+    Pcf,
+    Pcss,
+}
+
+impl ShadowFilterMode {
+    const ALL: [ShadowFilterMode; 4] = [
+        ShadowFilterMode::Off,
+        ShadowFilterMode::Hardware2x2,
+        ShadowFilterMode::Pcf,
+        ShadowFilterMode::Pcss,
+    ];
+
+    fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|mode| *mode == self).unwrap();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    fn as_u32(self) -> u32 {
+        match self {
+            ShadowFilterMode::Off => 0,
+            ShadowFilterMode::Hardware2x2 => 1,
+            ShadowFilterMode::Pcf => 2,
+            ShadowFilterMode::Pcss => 3,
+        }
+    }
+}
+
+// HUD: a monospace bitmap font atlas covering ASCII 32..128 laid out in a
+// 16x8 grid, rendered as one textured quad per character.
+const HUD_FONT_ATLAS_PATH: &str = "examples/ch02/font_atlas.png";
+const HUD_GLYPH_COLS: u32 = 16;
+const HUD_GLYPH_ROWS: u32 = 8;
+const HUD_GLYPH_PX: f32 = 16.0;
+const HUD_MAX_CHARS: u32 = 256;
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct HudVertex {
+    pos: [f32; 2],
+    uv: [f32; 2],
+}
+
+/// Lays out `text` as one quad per character on a pixel grid starting at
+/// `origin` (top-left, Y down), each sampling its glyph cell out of the font
+/// atlas. `\n` starts a new line instead of consuming a glyph slot, so the
+/// FPS/resolution/isolevel/colormap lines in `update_hud` can be built as a
+/// single string.
+fn build_hud_vertices(text: &str, origin: (f32, f32)) -> Vec<HudVertex> {
+    let mut vertices = Vec::with_capacity(text.len() * 6);
+    let cell_w = 1.0 / HUD_GLYPH_COLS as f32;
+    let cell_h = 1.0 / HUD_GLYPH_ROWS as f32;
+
+    let mut col = 0u32;
+    let mut row = 0u32;
+    let mut quad_count = 0u32;
+
+    for ch in text.chars() {
+        if quad_count >= HUD_MAX_CHARS {
+            break;
+        }
+        if ch == '\n' {
+            col = 0;
+            row += 1;
+            continue;
+        }
+        let code = ch as u32;
+        if !(32..128).contains(&code) {
+            continue;
+        }
+        let glyph = code - 32;
+        let atlas_col = (glyph % HUD_GLYPH_COLS) as f32;
+        let atlas_row = (glyph / HUD_GLYPH_COLS) as f32;
+        let u0 = atlas_col * cell_w;
+        let v0 = atlas_row * cell_h;
+        let u1 = u0 + cell_w;
+        let v1 = v0 + cell_h;
+
+        let x0 = origin.0 + col as f32 * HUD_GLYPH_PX;
+        let x1 = x0 + HUD_GLYPH_PX;
+        let y0 = origin.1 + row as f32 * HUD_GLYPH_PX;
+        let y1 = y0 + HUD_GLYPH_PX;
+
+        vertices.push(HudVertex { pos: [x0, y0], uv: [u0, v0] });
+        vertices.push(HudVertex { pos: [x1, y0], uv: [u1, v0] });
+        vertices.push(HudVertex { pos: [x0, y1], uv: [u0, v1] });
+        vertices.push(HudVertex { pos: [x0, y1], uv: [u0, v1] });
+        vertices.push(HudVertex { pos: [x1, y0], uv: [u1, v0] });
+        vertices.push(HudVertex { pos: [x1, y1], uv: [u1, v1] });
+
+        col += 1;
+        quad_count += 1;
+    }
+
+    vertices
+}
+
+// Post-process chain: the main pass now renders into an intermediate
+// `scene_color_view` instead of straight into the swapchain/capture view,
+// then an ordered list of fullscreen filter passes declared by
+// POSTPROCESS_PRESET_PATH filters it stage by stage before a final blit
+// lands the result on the view `record_frame` was actually asked to draw
+// into. A missing preset file means an empty chain: the scene still goes
+// through the intermediate texture but blits straight through unmodified.
+const POSTPROCESS_PRESET_PATH: &str = "examples/ch02/postprocess.preset";
+
+/// Parses one `<fragment-shader-path> <output-scale> <filter>` line per
+/// pass (`filter` is "nearest" or "linear", defaulting to linear); `#`
+/// starts a comment. Returns an empty chain when the file can't be read, so
+/// the effect list stays opt-in rather than a hard requirement.
+fn load_postprocess_preset(path: &str) -> Vec<(String, f32, wgpu::FilterMode)> {
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    source
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let shader_path = parts.next()?.to_string();
+            let scale = parts
+                .next()
+                .and_then(|s| s.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let filter = match parts.next() {
+                Some("nearest") => wgpu::FilterMode::Nearest,
+                _ => wgpu::FilterMode::Linear,
+            };
+            Some((shader_path, scale, filter))
+        })
+        .collect()
+}
+
+/// Creates a `TEXTURE_BINDING | RENDER_ATTACHMENT` target for one
+/// post-process stage; dimensions are clamped to at least 1px so a
+/// sub-1.0 `scale` chain can't collapse to a zero-sized texture.
+fn create_postprocess_target(
+    device: &wgpu::Device,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    label: &str,
+) -> wgpu::TextureView {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// One fullscreen filter stage in the post-process chain. Every stage
+/// shares the `fullscreen_vert.wgsl` vertex shader (none of them use a
+/// vertex buffer, just a 3-vertex fullscreen triangle), so only the
+/// fragment shader, sampler filter, and ping-pong target differ per pass.
+struct PostProcessPass {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    scale: f32,
+    color_view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Per-instance data for the instancing mode added in `create_instances`:
+/// one model matrix per blob in the grid, uploaded once and left untouched
+/// since the grid itself doesn't move (only the shared mesh animates).
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct InstanceRaw {
+    model: [[f32; 4]; 4],
+}
+
+impl InstanceRaw {
+    const ATTRIBS: [wgpu::VertexAttribute; 4] =
+        wgpu::vertex_attr_array![3 => Float32x4, 4 => Float32x4, 5 => Float32x4, 6 => Float32x4];
+
+    fn layout() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<InstanceRaw>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+/// Lays out `INSTANCE_GRID * INSTANCE_GRID` copies of the one GPU-generated
+/// mesh on an XZ grid, each as its own model matrix, so the same
+/// marching-cubes surface can be rendered as a field of blobs/gyroids with a
+/// single instanced draw call.
+fn create_instances() -> Vec<InstanceRaw> {
+    let half = (INSTANCE_GRID as f32 - 1.0) * INSTANCE_SPACING * 0.5;
+    (0..INSTANCE_GRID)
+        .flat_map(|z| (0..INSTANCE_GRID).map(move |x| (x, z)))
+        .map(|(x, z)| {
+            let translation = cgmath::Vector3::new(
+                x as f32 * INSTANCE_SPACING - half,
+                0.0,
+                z as f32 * INSTANCE_SPACING - half,
+            );
+            let model: [[f32; 4]; 4] = Matrix4::from_translation(translation).into();
+            InstanceRaw { model }
+        })
+        .collect()
+}
+
 struct State {
     init: ws::IWgpuInit,
     pipeline: wgpu::RenderPipeline,
@@ -53,6 +403,31 @@ struct State {
     cs_uniform_buffers: Vec<wgpu::Buffer>,
     cs_bind_groups: Vec<wgpu::BindGroup>,
 
+    instance_buffer: wgpu::Buffer,
+    instance_count: u32,
+
+    shadow_pipeline: wgpu::RenderPipeline,
+    shadow_texture_view: wgpu::TextureView,
+    shadow_bind_group: wgpu::BindGroup,
+    light_vp_buffer: wgpu::Buffer,
+    shadow_enabled: bool,
+
+    // filtering applied when the main fragment pass samples shadow_texture_view,
+    // plus the bias/light-size parameters that filter reads out of shadow_params_buffer
+    shadow_sample_bind_group: wgpu::BindGroup,
+    shadow_params_buffer: wgpu::Buffer,
+    shadow_filter_mode: ShadowFilterMode,
+    shadow_bias: f32,
+    shadow_light_size: f32,
+
+    // orbit/arcball camera: yaw/pitch/radius around the origin, driven by
+    // mouse drag + scroll in `input` and turned into `view_mat` in `update`.
+    camera_yaw: f32,
+    camera_pitch: f32,
+    camera_radius: f32,
+    camera_dragging: bool,
+    last_cursor_pos: Option<(f64, f64)>,
+
     view_mat: Matrix4<f32>,
     project_mat: Matrix4<f32>,
     msaa_texture_view: wgpu::TextureView,
@@ -62,6 +437,10 @@ struct State {
 
     resolution: u32,
     index_count: u32,
+    // Set the first time `compute_workgroups_per_axis` clamps, so the
+    // "device can't cover this resolution" warning only logs once instead
+    // of spamming every frame for the life of the run.
+    resolution_clamp_logged: std::cell::Cell<bool>,
 
     surface_type: u32,
     colormap_direction: u32,
@@ -70,13 +449,53 @@ struct State {
     scale: f32,
 
     rng: ThreadRng,
-    t0: std::time::Instant,
+    t0: Instant,
     random_shape_change: u32,
-    fps_counter: ws::FpsCounter,
+
+    textured_pipeline: wgpu::RenderPipeline,
+    texture_bind_group: wgpu::BindGroup,
+    textured_mode: bool,
+
+    render_target: RenderTarget,
+    capture_dir: std::path::PathBuf,
+    capture_frame: u32,
+    capture_frame_count: u32,
+
+    // HUD overlay: FPS/resolution/isolevel/colormap rendered as textured
+    // glyph quads in their own pipeline, drawn in a final pass inside the
+    // existing render pass instead of `fps_counter.print_fps` to stdout.
+    // `fps` is smoothed in `update_hud` since `ws::FpsCounter` only exposes
+    // a throttled stdout dump, not a readable value.
+    colormap_name: String,
+    hud_pipeline: wgpu::RenderPipeline,
+    hud_uniform_bind_group: wgpu::BindGroup,
+    hud_texture_bind_group: wgpu::BindGroup,
+    hud_uniform_buffer: wgpu::Buffer,
+    hud_vertex_buffer: wgpu::Buffer,
+    hud_vertex_count: u32,
+    last_update_instant: Instant,
+    fps: f32,
+
+    // Post-process chain: the scene renders into `scene_color_view`, each
+    // `postprocess_passes` entry filters the previous stage's texture, and
+    // `blit_*` copies whichever texture ends up last onto the view
+    // `record_frame` was asked to draw into. See POSTPROCESS_PRESET_PATH.
+    scene_color_view: wgpu::TextureView,
+    postprocess_passes: Vec<PostProcessPass>,
+    blit_pipeline: wgpu::RenderPipeline,
+    blit_bind_group_layout: wgpu::BindGroupLayout,
+    blit_sampler: wgpu::Sampler,
+    blit_bind_group: wgpu::BindGroup,
 }
 
 impl State {
-    async fn new(window: &Window, sample_count: u32, resolution: u32, colormap_name: &str) -> Self {
+    async fn new(
+        window: &Window,
+        sample_count: u32,
+        resolution: u32,
+        colormap_name: &str,
+        capture: Option<(std::path::PathBuf, u32)>,
+    ) -> Self {
         let limits = wgpu::Limits {
             max_storage_buffer_binding_size: 1024 * 1024 * 1024, //1024MB, defaulting to 128MB
             max_buffer_size: 1024 * 1024 * 1024,                 // 1024MB, defaulting to 256MB
@@ -93,37 +512,52 @@ impl State {
         let index_buffer_size = 4 * index_count;
         println!("resolution = {}", resol);
 
-        let vs_shader = init
-            .device
-            .create_shader_module(wgpu::include_wgsl!("../ch01/shader_vert.wgsl"));
-        let fs_shader = init
-            .device
-            .create_shader_module(wgpu::include_wgsl!("../ch01/shader_frag.wgsl"));
+        let wgsl_preprocessor = WgslPreprocessor::new(&resolve_wgsl_module);
 
-        let cs_value_file = include_str!("implicit_value.wgsl");
-        let cs_func_file = include_str!("implicit_func.wgsl");
-        let cs_surface_file = include_str!("implicit_surface.wgsl");
+        let vs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(
+                wgsl_preprocessor.process("../ch01/shader_vert.wgsl").into(),
+            ),
+        });
+        let fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(
+                wgsl_preprocessor.process("../ch01/shader_frag.wgsl").into(),
+            ),
+        });
 
         let cs_value = init
             .device
             .create_shader_module(wgpu::ShaderModuleDescriptor {
                 label: Some("Compute Value Shader"),
-                source: wgpu::ShaderSource::Wgsl([cs_func_file, cs_value_file].join("\n").into()),
+                source: wgpu::ShaderSource::Wgsl(
+                    wgsl_preprocessor.process("implicit_value.wgsl").into(),
+                ),
             });
 
         let cs_comp = init
             .device
             .create_shader_module(wgpu::ShaderModuleDescriptor {
                 label: Some("Compute Surface Shader"),
-                source: wgpu::ShaderSource::Wgsl([cs_func_file, cs_surface_file].join("\n").into()),
+                source: wgpu::ShaderSource::Wgsl(
+                    wgsl_preprocessor.process("implicit_surface.wgsl").into(),
+                ),
             });
 
         // uniform data
-        let camera_position = (2.0, 2.0, 3.0).into();
+        let camera_position: cgmath::Point3<f32> = (2.0, 2.0, 3.0).into();
         let look_direction = (0.0, 0.0, 0.0).into();
         let up_direction = cgmath::Vector3::unit_y();
         let light_direction = [-0.5f32, -0.5, -0.5];
 
+        // Arcball state derived from the initial camera position, so the
+        // first frame renders identically to before this controller existed;
+        // `update` recomputes `view_mat` from yaw/pitch/radius afterwards.
+        let camera_radius = (camera_position - cgmath::Point3::new(0.0, 0.0, 0.0)).magnitude();
+        let camera_yaw = camera_position.x.atan2(camera_position.z);
+        let camera_pitch = (camera_position.y / camera_radius).asin();
+
         let (view_mat, project_mat, _) = ws::create_vp_mat(
             camera_position,
             look_direction,
@@ -179,6 +613,148 @@ impl State {
         init.queue
             .write_buffer(&material_uniform_buffer, 0, cast_slice(material.as_ref()));
 
+        // shadow map: render the same instanced mesh from the light's
+        // orthographic point of view into a depth-only texture, then sample
+        // it back in shader_frag.wgsl with a filter chosen by shadow_filter_mode.
+        const SHADOW_MAP_SIZE: u32 = 2048;
+        let shadow_texture = init.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Map Texture"),
+            size: wgpu::Extent3d {
+                width: SHADOW_MAP_SIZE,
+                height: SHADOW_MAP_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let shadow_texture_view = shadow_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let shadow_sampler = init.device.create_sampler(&wgpu::SamplerDescriptor {
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let light_ortho_half_extent = 2.0f32;
+        let light_view = Matrix4::look_at_rh(
+            cgmath::Point3::new(-light_direction[0], -light_direction[1], -light_direction[2]) * 4.0,
+            cgmath::Point3::new(0.0, 0.0, 0.0),
+            cgmath::Vector3::unit_y(),
+        );
+        let light_proj = cgmath::ortho(
+            -light_ortho_half_extent,
+            light_ortho_half_extent,
+            -light_ortho_half_extent,
+            light_ortho_half_extent,
+            0.1,
+            20.0,
+        );
+        let light_vp: [[f32; 4]; 4] = (light_proj * light_view).into();
+
+        let light_vp_buffer = init.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light View-Projection Buffer"),
+            contents: cast_slice(&[light_vp]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let (shadow_bind_group_layout, shadow_bind_group) = ws::create_bind_group(
+            &init.device,
+            vec![wgpu::ShaderStages::VERTEX],
+            &[light_vp_buffer.as_entire_binding()],
+        );
+
+        // filter mode/bias/light-size read by the main fragment pass when it
+        // samples shadow_texture_view; cycled with F and tuned with Z/X (bias)
+        // and C/V (PCSS light size) so shadow quality can be tuned live.
+        let shadow_filter_mode = ShadowFilterMode::Pcf;
+        let shadow_bias = 0.003f32;
+        let shadow_light_size = 0.4f32;
+        let shadow_params_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow Params Buffer"),
+            size: 16, // u32 filter_mode, u32 _pad, f32 bias, f32 light_size
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        init.queue.write_buffer(
+            &shadow_params_buffer,
+            0,
+            cast_slice(&[shadow_filter_mode.as_u32(), 0u32]),
+        );
+        init.queue.write_buffer(
+            &shadow_params_buffer,
+            8,
+            cast_slice(&[shadow_bias, shadow_light_size]),
+        );
+
+        let shadow_sample_bind_group_layout =
+            init.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Shadow Sample Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Depth,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+        let shadow_sample_bind_group = init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shadow Sample Bind Group"),
+            layout: &shadow_sample_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: light_vp_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&shadow_texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&shadow_sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: shadow_params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
         // uniform bind group for vertex shader
         let (vert_bind_group_layout, vert_bind_group) = ws::create_bind_group(
             &init.device,
@@ -212,13 +788,14 @@ impl State {
                 step_mode: wgpu::VertexStepMode::Vertex,
                 attributes: &wgpu::vertex_attr_array![2 => Float32x4], // col
             },
+            InstanceRaw::layout(),
         ];
 
         let pipeline_layout = init
             .device
             .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[&vert_bind_group_layout, &frag_bind_group_layout],
+                bind_group_layouts: &[&vert_bind_group_layout, &frag_bind_group_layout, &shadow_sample_bind_group_layout],
                 push_constant_ranges: &[],
             });
 
@@ -231,9 +808,469 @@ impl State {
         };
         let pipeline = ppl.new(&init);
 
+        // Triplanar textured mode: sample `TRIPLANAR_TEXTURE_PATH` three
+        // times projected along X/Y/Z and blend by the squared normal
+        // components in `triplanar_frag.wgsl`, as an alternative to the
+        // colormap shading `fs_shader` already does.
+        let triplanar_image = image::open(TRIPLANAR_TEXTURE_PATH)
+            .expect("failed to load triplanar texture")
+            .to_rgba8();
+        let (triplanar_width, triplanar_height) = triplanar_image.dimensions();
+        let triplanar_size = wgpu::Extent3d {
+            width: triplanar_width,
+            height: triplanar_height,
+            depth_or_array_layers: 1,
+        };
+        let triplanar_texture = init.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Triplanar Texture"),
+            size: triplanar_size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        init.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &triplanar_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &triplanar_image,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * triplanar_width),
+                rows_per_image: Some(triplanar_height),
+            },
+            triplanar_size,
+        );
+        let triplanar_texture_view = triplanar_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let triplanar_sampler = init.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let texture_bind_group_layout = init.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Triplanar Texture Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let texture_bind_group = init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Triplanar Texture Bind Group"),
+            layout: &texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&triplanar_texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&triplanar_sampler),
+                },
+            ],
+        });
+
+        let triplanar_fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Triplanar Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("triplanar_frag.wgsl").into()),
+        });
+        let textured_pipeline_layout = init.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Textured Render Pipeline Layout"),
+            bind_group_layouts: &[
+                &vert_bind_group_layout,
+                &frag_bind_group_layout,
+                &texture_bind_group_layout,
+                &shadow_sample_bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+        let mut textured_ppl = ws::IRenderPipeline {
+            vs_shader: Some(&vs_shader),
+            fs_shader: Some(&triplanar_fs_shader),
+            pipeline_layout: Some(&textured_pipeline_layout),
+            vertex_buffer_layout: &vertex_buffer_layouts,
+            ..Default::default()
+        };
+        let textured_pipeline = textured_ppl.new(&init);
+
         let msaa_texture_view = ws::create_msaa_texture_view(&init);
         let depth_texture_view = ws::create_depth_view(&init);
 
+        let shadow_pipeline_layout = init.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shadow Pipeline Layout"),
+            bind_group_layouts: &[&shadow_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let mut shadow_ppl = ws::IRenderPipeline {
+            vs_shader: Some(&vs_shader),
+            fs_shader: None,
+            pipeline_layout: Some(&shadow_pipeline_layout),
+            vertex_buffer_layout: &vertex_buffer_layouts,
+            depth_format: Some(wgpu::TextureFormat::Depth32Float),
+            ..Default::default()
+        };
+        let shadow_pipeline = shadow_ppl.new(&init);
+
+        // HUD: a final screen-space pass drawing textured glyph quads built
+        // by `build_hud_vertices`, so FPS/resolution/isolevel/colormap are
+        // readable while tweaking the surface live instead of only printed
+        // to the terminal.
+        let hud_vs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("HUD Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("hud_vert.wgsl").into()),
+        });
+        let hud_fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("HUD Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("hud_frag.wgsl").into()),
+        });
+
+        let hud_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("HUD Uniform Buffer"),
+            size: 16, // vec2<f32> screen_size, padded to the 16-byte uniform minimum
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        init.queue.write_buffer(
+            &hud_uniform_buffer,
+            0,
+            cast_slice(&[init.config.width as f32, init.config.height as f32, 0.0, 0.0]),
+        );
+
+        let (hud_uniform_bind_group_layout, hud_uniform_bind_group) = ws::create_bind_group(
+            &init.device,
+            vec![wgpu::ShaderStages::VERTEX],
+            &[hud_uniform_buffer.as_entire_binding()],
+        );
+
+        let hud_atlas_image = image::open(HUD_FONT_ATLAS_PATH)
+            .expect("failed to load HUD font atlas")
+            .to_rgba8();
+        let (hud_atlas_width, hud_atlas_height) = hud_atlas_image.dimensions();
+        let hud_atlas_size = wgpu::Extent3d {
+            width: hud_atlas_width,
+            height: hud_atlas_height,
+            depth_or_array_layers: 1,
+        };
+        let hud_atlas_texture = init.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("HUD Font Atlas Texture"),
+            size: hud_atlas_size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        init.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &hud_atlas_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &hud_atlas_image,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * hud_atlas_width),
+                rows_per_image: Some(hud_atlas_height),
+            },
+            hud_atlas_size,
+        );
+        let hud_atlas_view = hud_atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let hud_sampler = init.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let hud_texture_bind_group_layout = init.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("HUD Texture Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let hud_texture_bind_group = init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("HUD Texture Bind Group"),
+            layout: &hud_texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&hud_atlas_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&hud_sampler),
+                },
+            ],
+        });
+
+        let hud_pipeline_layout = init.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("HUD Pipeline Layout"),
+            bind_group_layouts: &[&hud_uniform_bind_group_layout, &hud_texture_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let hud_vertex_buffer_layout = [VertexBufferLayout {
+            array_stride: std::mem::size_of::<HudVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2],
+        }];
+        let mut hud_ppl = ws::IRenderPipeline {
+            vs_shader: Some(&hud_vs_shader),
+            fs_shader: Some(&hud_fs_shader),
+            pipeline_layout: Some(&hud_pipeline_layout),
+            vertex_buffer_layout: &hud_vertex_buffer_layout,
+            depth_format: None, // always drawn on top, regardless of scene depth
+            ..Default::default()
+        };
+        let hud_pipeline = hud_ppl.new(&init);
+
+        let hud_vertex_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("HUD Vertex Buffer"),
+            size: (HUD_MAX_CHARS as u64) * 6 * std::mem::size_of::<HudVertex>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Post-process chain (see POSTPROCESS_PRESET_PATH doc comment above):
+        // the main pass below renders into `scene_color_view` instead of
+        // straight into the view `record_frame` is asked to draw into.
+        let postprocess_format = init.config.format;
+        let scene_color_view = create_postprocess_target(
+            &init.device,
+            init.config.width,
+            init.config.height,
+            postprocess_format,
+            "Scene Color Target",
+        );
+
+        let fullscreen_vs_shader = init
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Fullscreen Triangle Vertex Shader"),
+                source: wgpu::ShaderSource::Wgsl(include_str!("fullscreen_vert.wgsl").into()),
+            });
+
+        let postprocess_preset = load_postprocess_preset(POSTPROCESS_PRESET_PATH);
+        let mut postprocess_passes: Vec<PostProcessPass> =
+            Vec::with_capacity(postprocess_preset.len());
+        let mut prev_width = init.config.width;
+        let mut prev_height = init.config.height;
+
+        for (index, (shader_path, scale, filter)) in postprocess_preset.iter().enumerate() {
+            let fs_source = std::fs::read_to_string(shader_path).unwrap_or_else(|err| {
+                panic!("failed to read post-process shader {shader_path:?}: {err}")
+            });
+            let fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(&format!("Post-process Pass {index} Fragment Shader")),
+                source: wgpu::ShaderSource::Wgsl(fs_source.into()),
+            });
+
+            let bind_group_layout =
+                init.device
+                    .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                        label: Some("Post-process Pass Bind Group Layout"),
+                        entries: &[
+                            wgpu::BindGroupLayoutEntry {
+                                binding: 0,
+                                visibility: wgpu::ShaderStages::FRAGMENT,
+                                ty: wgpu::BindingType::Texture {
+                                    multisampled: false,
+                                    view_dimension: wgpu::TextureViewDimension::D2,
+                                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                                },
+                                count: None,
+                            },
+                            wgpu::BindGroupLayoutEntry {
+                                binding: 1,
+                                visibility: wgpu::ShaderStages::FRAGMENT,
+                                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                                count: None,
+                            },
+                        ],
+                    });
+            let sampler = init.device.create_sampler(&wgpu::SamplerDescriptor {
+                address_mode_u: wgpu::AddressMode::ClampToEdge,
+                address_mode_v: wgpu::AddressMode::ClampToEdge,
+                mag_filter: *filter,
+                min_filter: *filter,
+                ..Default::default()
+            });
+            let bind_group = {
+                let source_view: &wgpu::TextureView = match postprocess_passes.last() {
+                    Some(prev) => &prev.color_view,
+                    None => &scene_color_view,
+                };
+                init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Post-process Pass Bind Group"),
+                    layout: &bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(source_view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&sampler),
+                        },
+                    ],
+                })
+            };
+
+            let pipeline_layout =
+                init.device
+                    .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: Some("Post-process Pass Pipeline Layout"),
+                        bind_group_layouts: &[&bind_group_layout],
+                        push_constant_ranges: &[],
+                    });
+            let mut pp_ppl = ws::IRenderPipeline {
+                vs_shader: Some(&fullscreen_vs_shader),
+                fs_shader: Some(&fs_shader),
+                pipeline_layout: Some(&pipeline_layout),
+                vertex_buffer_layout: &[],
+                depth_format: None,
+                ..Default::default()
+            };
+            let pipeline = pp_ppl.new(&init);
+
+            let pass_width = ((prev_width as f32) * scale).round().max(1.0) as u32;
+            let pass_height = ((prev_height as f32) * scale).round().max(1.0) as u32;
+            let color_view = create_postprocess_target(
+                &init.device,
+                pass_width,
+                pass_height,
+                postprocess_format,
+                "Post-process Pass Color Target",
+            );
+
+            postprocess_passes.push(PostProcessPass {
+                pipeline,
+                bind_group_layout,
+                sampler,
+                scale: *scale,
+                color_view,
+                bind_group,
+            });
+
+            prev_width = pass_width;
+            prev_height = pass_height;
+        }
+
+        // Final blit: copies whichever texture the chain above ends on
+        // (the last pass's output, or `scene_color_view` when the preset is
+        // empty) onto the view `record_frame` was actually asked to draw
+        // into, since the swapchain/capture texture itself isn't sampleable.
+        let blit_fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Post-process Blit Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("blit_frag.wgsl").into()),
+        });
+        let blit_bind_group_layout =
+            init.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Post-process Blit Bind Group Layout"),
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                    ],
+                });
+        let blit_sampler = init.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let blit_bind_group = {
+            let final_source_view: &wgpu::TextureView = match postprocess_passes.last() {
+                Some(pass) => &pass.color_view,
+                None => &scene_color_view,
+            };
+            init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Post-process Blit Bind Group"),
+                layout: &blit_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(final_source_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&blit_sampler),
+                    },
+                ],
+            })
+        };
+        let blit_pipeline_layout =
+            init.device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Post-process Blit Pipeline Layout"),
+                    bind_group_layouts: &[&blit_bind_group_layout],
+                    push_constant_ranges: &[],
+                });
+        let mut blit_ppl = ws::IRenderPipeline {
+            vs_shader: Some(&fullscreen_vs_shader),
+            fs_shader: Some(&blit_fs_shader),
+            pipeline_layout: Some(&blit_pipeline_layout),
+            vertex_buffer_layout: &[],
+            depth_format: None,
+            ..Default::default()
+        };
+        let blit_pipeline = blit_ppl.new(&init);
+
         // create compute pipeline for value
         let volume_elements = resol * resol * resol;
         let cs_value_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
@@ -318,7 +1355,8 @@ impl State {
             size: vertex_buffer_size as u64,
             usage: wgpu::BufferUsages::VERTEX
                 | wgpu::BufferUsages::STORAGE
-                | wgpu::BufferUsages::COPY_DST,
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
@@ -327,7 +1365,8 @@ impl State {
             size: vertex_buffer_size as u64,
             usage: wgpu::BufferUsages::VERTEX
                 | wgpu::BufferUsages::STORAGE
-                | wgpu::BufferUsages::COPY_DST,
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
@@ -345,7 +1384,8 @@ impl State {
             size: index_buffer_size as u64,
             usage: wgpu::BufferUsages::INDEX
                 | wgpu::BufferUsages::STORAGE
-                | wgpu::BufferUsages::COPY_DST,
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
@@ -355,7 +1395,8 @@ impl State {
             size: 16,
             usage: wgpu::BufferUsages::INDIRECT
                 | wgpu::BufferUsages::STORAGE
-                | wgpu::BufferUsages::COPY_DST,
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
@@ -439,6 +1480,16 @@ impl State {
                 entry_point: "cs_main",
             });
 
+        let instances = create_instances();
+        let instance_count = instances.len() as u32;
+        let instance_buffer = init
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Instance Buffer"),
+                contents: cast_slice(&instances),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            });
+
         Self {
             init,
             pipeline,
@@ -466,6 +1517,27 @@ impl State {
             ],
             cs_bind_groups: vec![cs_value_bind_group, cs_bind_group],
 
+            instance_buffer,
+            instance_count,
+
+            shadow_pipeline,
+            shadow_texture_view,
+            shadow_bind_group,
+            light_vp_buffer,
+            shadow_enabled: true,
+
+            shadow_sample_bind_group,
+            shadow_params_buffer,
+            shadow_filter_mode,
+            shadow_bias,
+            shadow_light_size,
+
+            camera_yaw,
+            camera_pitch,
+            camera_radius,
+            camera_dragging: false,
+            last_cursor_pos: None,
+
             view_mat,
             project_mat,
             msaa_texture_view,
@@ -476,6 +1548,7 @@ impl State {
 
             resolution: resol,
             index_count,
+            resolution_clamp_logged: std::cell::Cell::new(false),
             surface_type: 2,
             colormap_direction: 1,
             colormap_reverse: 0,
@@ -483,9 +1556,34 @@ impl State {
             scale: 1.0,
 
             rng: rand::thread_rng(),
-            t0: std::time::Instant::now(),
+            t0: Instant::now(),
             random_shape_change: 0,
-            fps_counter: ws::FpsCounter::default(),
+
+            textured_pipeline,
+            texture_bind_group,
+            textured_mode: false,
+
+            render_target: if capture.is_some() { RenderTarget::Offscreen } else { RenderTarget::Window },
+            capture_dir: capture.as_ref().map(|(dir, _)| dir.clone()).unwrap_or_default(),
+            capture_frame: 0,
+            capture_frame_count: capture.map(|(_, count)| count).unwrap_or(0),
+
+            colormap_name: colormap_name.to_string(),
+            hud_pipeline,
+            hud_uniform_bind_group,
+            hud_texture_bind_group,
+            hud_uniform_buffer,
+            hud_vertex_buffer,
+            hud_vertex_count: 0,
+            last_update_instant: Instant::now(),
+            fps: 0.0,
+
+            scene_color_view,
+            postprocess_passes,
+            blit_pipeline,
+            blit_bind_group_layout,
+            blit_sampler,
+            blit_bind_group,
         }
     }
 
@@ -504,9 +1602,90 @@ impl State {
             if self.init.sample_count > 1 {
                 self.msaa_texture_view = ws::create_msaa_texture_view(&self.init);
             }
+            self.resize_postprocess_targets();
         }
     }
 
+    /// Rebuilds the post-process chain's intermediate color targets (and the
+    /// bind groups that sample them) at the new window size. Pipelines and
+    /// samplers don't depend on the surface size, so only the targets and
+    /// the bind groups chaining them together need to change here.
+    fn resize_postprocess_targets(&mut self) {
+        let format = self.init.config.format;
+        self.scene_color_view = create_postprocess_target(
+            &self.init.device,
+            self.init.config.width,
+            self.init.config.height,
+            format,
+            "Scene Color Target",
+        );
+
+        let mut prev_width = self.init.config.width;
+        let mut prev_height = self.init.config.height;
+        for index in 0..self.postprocess_passes.len() {
+            let scale = self.postprocess_passes[index].scale;
+            let pass_width = ((prev_width as f32) * scale).round().max(1.0) as u32;
+            let pass_height = ((prev_height as f32) * scale).round().max(1.0) as u32;
+            let color_view = create_postprocess_target(
+                &self.init.device,
+                pass_width,
+                pass_height,
+                format,
+                "Post-process Pass Color Target",
+            );
+
+            let bind_group = {
+                let source_view: &wgpu::TextureView = if index == 0 {
+                    &self.scene_color_view
+                } else {
+                    &self.postprocess_passes[index - 1].color_view
+                };
+                self.init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Post-process Pass Bind Group"),
+                    layout: &self.postprocess_passes[index].bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(source_view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(
+                                &self.postprocess_passes[index].sampler,
+                            ),
+                        },
+                    ],
+                })
+            };
+
+            let pass = &mut self.postprocess_passes[index];
+            pass.color_view = color_view;
+            pass.bind_group = bind_group;
+
+            prev_width = pass_width;
+            prev_height = pass_height;
+        }
+
+        let final_source_view: &wgpu::TextureView = match self.postprocess_passes.last() {
+            Some(pass) => &pass.color_view,
+            None => &self.scene_color_view,
+        };
+        self.blit_bind_group = self.init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Post-process Blit Bind Group"),
+            layout: &self.blit_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(final_source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.blit_sampler),
+                },
+            ],
+        });
+    }
+
     #[allow(unused_variables)]
     fn input(&mut self, event: &WindowEvent) -> bool {
         match event {
@@ -562,13 +1741,214 @@ impl State {
                     }
                     true
                 }
+                VirtualKeyCode::E => {
+                    if let Err(err) = self.export_mesh("surface.obj") {
+                        eprintln!("mesh export failed: {:?}", err);
+                    } else {
+                        println!("exported surface.obj");
+                    }
+                    true
+                }
+                VirtualKeyCode::H => {
+                    self.shadow_enabled = !self.shadow_enabled;
+                    true
+                }
+                VirtualKeyCode::F => {
+                    self.shadow_filter_mode = self.shadow_filter_mode.next();
+                    println!("shadow_filter_mode = {:?}", self.shadow_filter_mode);
+                    true
+                }
+                VirtualKeyCode::Z => {
+                    self.shadow_bias = (self.shadow_bias - 0.0005).max(0.0);
+                    true
+                }
+                VirtualKeyCode::X => {
+                    self.shadow_bias += 0.0005;
+                    true
+                }
+                VirtualKeyCode::C => {
+                    self.shadow_light_size = (self.shadow_light_size - 0.05).max(0.0);
+                    true
+                }
+                VirtualKeyCode::V => {
+                    self.shadow_light_size += 0.05;
+                    true
+                }
+                VirtualKeyCode::T => {
+                    self.textured_mode = !self.textured_mode;
+                    true
+                }
                 _ => false,
             },
+            WindowEvent::MouseInput {
+                button: MouseButton::Left,
+                state,
+                ..
+            } => {
+                self.camera_dragging = *state == ElementState::Pressed;
+                if !self.camera_dragging {
+                    self.last_cursor_pos = None;
+                }
+                true
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let pos = (position.x, position.y);
+                if self.camera_dragging {
+                    if let Some((last_x, last_y)) = self.last_cursor_pos {
+                        let dx = (pos.0 - last_x) as f32;
+                        let dy = (pos.1 - last_y) as f32;
+                        self.camera_yaw -= dx * 0.005;
+                        self.camera_pitch = (self.camera_pitch + dy * 0.005)
+                            .clamp(-1.54, 1.54); // keep away from the poles
+                    }
+                }
+                self.last_cursor_pos = Some(pos);
+                self.camera_dragging
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let scroll = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.1,
+                };
+                self.camera_radius = (self.camera_radius - scroll * 0.3).clamp(1.0, 20.0);
+                true
+            }
             _ => false,
         }
     }
 
+    /// Reads back the compute-generated `cs_position_buffer`,
+    /// `cs_normal_buffer`, and `cs_index_buffer` and writes them to a
+    /// Wavefront OBJ file, so the marching-cubes output can be used outside
+    /// this demo. The real index count is taken from the indirect
+    /// buffer's first word rather than the worst-case `index_count`, since
+    /// not every cell crosses the isosurface.
+    fn export_mesh(&self, path: &str) -> std::io::Result<()> {
+        let position_size = self.cs_vertex_buffers[1].size();
+        let normal_size = self.cs_vertex_buffers[2].size();
+        let index_size = self.cs_index_buffer.size();
+        let indirect_size = self.cs_uniform_buffers[4].size();
+
+        let staging_position = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Export Position Staging Buffer"),
+            size: position_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let staging_normal = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Export Normal Staging Buffer"),
+            size: normal_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let staging_index = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Export Index Staging Buffer"),
+            size: index_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let staging_indirect = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Export Indirect Staging Buffer"),
+            size: indirect_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .init
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Export Readback Encoder") });
+        encoder.copy_buffer_to_buffer(&self.cs_vertex_buffers[1], 0, &staging_position, 0, position_size);
+        encoder.copy_buffer_to_buffer(&self.cs_vertex_buffers[2], 0, &staging_normal, 0, normal_size);
+        encoder.copy_buffer_to_buffer(&self.cs_index_buffer, 0, &staging_index, 0, index_size);
+        encoder.copy_buffer_to_buffer(&self.cs_uniform_buffers[4], 0, &staging_indirect, 0, indirect_size);
+        self.init.queue.submit(iter::once(encoder.finish()));
+
+        for buffer in [&staging_position, &staging_normal, &staging_index, &staging_indirect] {
+            buffer.slice(..).map_async(wgpu::MapMode::Read, |_| {});
+        }
+        self.init.device.poll(wgpu::Maintain::Wait);
+
+        let indirect_bytes = staging_indirect.slice(..).get_mapped_range();
+        let real_index_count = u32::from_le_bytes(indirect_bytes[0..4].try_into().unwrap()) as usize;
+        drop(indirect_bytes);
+        staging_indirect.unmap();
+
+        let indices: Vec<u32> = {
+            let bytes = staging_index.slice(..).get_mapped_range();
+            let all: &[u32] = bytemuck::cast_slice(&bytes);
+            all[..real_index_count].to_vec()
+        };
+        staging_index.unmap();
+
+        // `positions`/`normals` are sized for the worst case (every marching
+        // cube emitting its max 5 triangles), not what this surface actually
+        // produced - slicing to the highest index `indices` references keeps
+        // the OBJ from dragging along tens of millions of zero-filled lines.
+        let real_vertex_count = indices.iter().copied().max().map_or(0, |max| max as usize + 1);
+
+        let positions: Vec<[f32; 4]> = {
+            let bytes = staging_position.slice(..).get_mapped_range();
+            let all: &[[f32; 4]] = bytemuck::cast_slice(&bytes);
+            all[..real_vertex_count].to_vec()
+        };
+        staging_position.unmap();
+
+        let normals: Vec<[f32; 4]> = {
+            let bytes = staging_normal.slice(..).get_mapped_range();
+            let all: &[[f32; 4]] = bytemuck::cast_slice(&bytes);
+            all[..real_vertex_count].to_vec()
+        };
+        staging_normal.unmap();
+
+        let mut obj = String::new();
+        for p in &positions {
+            obj.push_str(&format!("v {} {} {}\n", p[0], p[1], p[2]));
+        }
+        for n in &normals {
+            obj.push_str(&format!("vn {} {} {}\n", n[0], n[1], n[2]));
+        }
+        for tri in indices.chunks(3) {
+            if tri.len() < 3 {
+                break;
+            }
+            obj.push_str(&format!(
+                "f {}//{} {}//{} {}//{}\n",
+                tri[0] + 1,
+                tri[0] + 1,
+                tri[1] + 1,
+                tri[1] + 1,
+                tri[2] + 1,
+                tri[2] + 1,
+            ));
+        }
+
+        std::fs::write(path, obj)
+    }
+
     fn update(&mut self, dt: std::time::Duration) {
+        // recompute the arcball camera from the yaw/pitch/radius accumulated
+        // in `input`, and keep the light uniform's eye_position in step so
+        // specular highlights track the camera rather than the original
+        // fixed viewpoint.
+        let camera_position = cgmath::Point3::new(
+            self.camera_radius * self.camera_pitch.cos() * self.camera_yaw.sin(),
+            self.camera_radius * self.camera_pitch.sin(),
+            self.camera_radius * self.camera_pitch.cos() * self.camera_yaw.cos(),
+        );
+        let (view_mat, _, _) = ws::create_vp_mat(
+            camera_position,
+            (0.0, 0.0, 0.0).into(),
+            cgmath::Vector3::unit_y(),
+            self.init.config.width as f32 / self.init.config.height as f32,
+        );
+        self.view_mat = view_mat;
+
+        let eye_position: &[f32; 3] = camera_position.as_ref();
+        self.init
+            .queue
+            .write_buffer(&self.uniform_buffers[1], 16, cast_slice(eye_position));
+
         // update uniform buffer
         let dt1 = self.rotation_speed * dt.as_secs_f32();
 
@@ -598,7 +1978,7 @@ impl State {
         let elapsed = self.t0.elapsed();
         if elapsed >= std::time::Duration::from_secs(5) && self.random_shape_change == 0 {
             self.surface_type = self.rng.gen_range(0..=8) as u32;
-            self.t0 = std::time::Instant::now();
+            self.t0 = Instant::now();
             println!(
                 "key = {:?}, surface_type = {:?}",
                 self.surface_type,
@@ -641,9 +2021,61 @@ impl State {
         self.init
             .queue
             .write_buffer(&self.cs_uniform_buffers[4], 0, cast_slice(&indirect_array));
+
+        self.init.queue.write_buffer(
+            &self.shadow_params_buffer,
+            0,
+            cast_slice(&[self.shadow_filter_mode.as_u32(), 0u32]),
+        );
+        self.init.queue.write_buffer(
+            &self.shadow_params_buffer,
+            8,
+            cast_slice(&[self.shadow_bias, self.shadow_light_size]),
+        );
+
+        self.update_hud((self.init.config.width, self.init.config.height));
+    }
+
+    /// Smooths the FPS from the wall-clock delta since the last call (there's
+    /// no reading `ws::FpsCounter`'s internal average, only its stdout dump),
+    /// rebuilds the HUD vertex buffer for the current frame's text, and
+    /// refreshes the screen-size uniform so glyph quads stay pixel-accurate
+    /// across a resize.
+    fn update_hud(&mut self, size: (u32, u32)) {
+        let now = Instant::now();
+        let dt = now.duration_since(self.last_update_instant).as_secs_f32();
+        self.last_update_instant = now;
+        if dt > 0.0 {
+            self.fps = self.fps * 0.9 + (1.0 / dt) * 0.1;
+        }
+
+        let text = format!(
+            "FPS: {:.1}\n{}x{}\nisolevel: {:.2}\ncolormap: {}\nshadow: {:?}",
+            self.fps, size.0, size.1, self.isolevel, self.colormap_name, self.shadow_filter_mode
+        );
+        let vertices = build_hud_vertices(&text, (8.0, 8.0));
+        self.hud_vertex_count = vertices.len() as u32;
+        self.init
+            .queue
+            .write_buffer(&self.hud_vertex_buffer, 0, cast_slice(&vertices));
+
+        let screen_size = [size.0 as f32, size.1 as f32, 0.0, 0.0];
+        self.init
+            .queue
+            .write_buffer(&self.hud_uniform_buffer, 0, cast_slice(&screen_size));
     }
 
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        match self.render_target {
+            RenderTarget::Window => self.render_to_surface(),
+            RenderTarget::Offscreen => {
+                self.render_to_offscreen();
+                Ok(())
+            }
+        }
+    }
+
+    fn render_to_surface(&mut self) -> Result<(), wgpu::SurfaceError> {
         let output = self.init.surface.get_current_texture()?;
         let view = output
             .texture
@@ -656,6 +2088,128 @@ impl State {
                     label: Some("Render Encoder"),
                 });
 
+        self.record_frame(&mut encoder, &view);
+
+        self.init.queue.submit(iter::once(encoder.finish()));
+
+        output.present();
+
+        Ok(())
+    }
+
+    /// Renders one frame into an offscreen texture the same size as the
+    /// surface, reads it back, and writes it to
+    /// `self.capture_dir/frame_NNNN.png` instead of presenting. Used for the
+    /// `--capture` CLI mode so turntable animations can be produced without
+    /// a live window to present to.
+    fn render_to_offscreen(&mut self) {
+        let size = wgpu::Extent3d {
+            width: self.init.config.width,
+            height: self.init.config.height,
+            depth_or_array_layers: 1,
+        };
+        let offscreen_texture = self.init.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Capture Offscreen Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.init.config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = offscreen_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder =
+            self.init
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Capture Encoder"),
+                });
+        self.record_frame(&mut encoder, &view);
+
+        // `bytes_per_row` must be padded to `COPY_BYTES_PER_ROW_ALIGNMENT`.
+        let unpadded_bytes_per_row = 4 * self.init.config.width;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = ((unpadded_bytes_per_row + align - 1) / align) * align;
+        let output_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Capture Readback Buffer"),
+            size: (padded_bytes_per_row * self.init.config.height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &offscreen_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &output_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(self.init.config.height),
+                },
+            },
+            size,
+        );
+        self.init.queue.submit(iter::once(encoder.finish()));
+
+        output_buffer.slice(..).map_async(wgpu::MapMode::Read, |_| {});
+        self.init.device.poll(wgpu::Maintain::Wait);
+
+        let padded = output_buffer.slice(..).get_mapped_range();
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * self.init.config.height) as usize);
+        for row in padded.chunks(padded_bytes_per_row as usize) {
+            pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+        }
+        drop(padded);
+        output_buffer.unmap();
+
+        std::fs::create_dir_all(&self.capture_dir).expect("failed to create capture output dir");
+        let frame_path = self
+            .capture_dir
+            .join(format!("frame_{:04}.png", self.capture_frame));
+        image::save_buffer(
+            &frame_path,
+            &pixels,
+            self.init.config.width,
+            self.init.config.height,
+            image::ColorType::Rgba8,
+        )
+        .expect("failed to write captured frame");
+
+        self.capture_frame += 1;
+    }
+
+    /// Workgroups per axis for the `resolution^3` value/vertex compute
+    /// dispatches, clamped to `max_compute_workgroups_per_dimension` so a
+    /// browser's tighter WebGPU limit can't turn into a validation error;
+    /// tiling the dispatch to recover the clamped coverage would need an
+    /// offset uniform threaded into implicit_value.wgsl/implicit_surface.wgsl,
+    /// so for now this only logs once if a device can't cover the resolution.
+    fn compute_workgroups_per_axis(&self) -> u32 {
+        let wanted = self.resolution / 8;
+        let max_dim = self.init.device.limits().max_compute_workgroups_per_dimension;
+        if wanted > max_dim && !self.resolution_clamp_logged.replace(true) {
+            eprintln!(
+                "resolution {} wants {} compute workgroups per axis, but this device only supports {}; clamping",
+                self.resolution, wanted, max_dim
+            );
+        }
+        wanted.min(max_dim)
+    }
+
+    /// Records the compute dispatches, shadow pass, main color pass, and
+    /// post-process passes into `encoder`: the main pass draws into
+    /// `scene_color_view`, and the final blit writes the result into `view`.
+    /// Shared between the swapchain path and the offscreen capture path so
+    /// both stay in sync.
+    fn record_frame(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let workgroups_per_axis = self.compute_workgroups_per_axis();
+
         // compute pass for value
         {
             let mut cs_index_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
@@ -664,9 +2218,9 @@ impl State {
             cs_index_pass.set_pipeline(&self.cs_pipelines[0]);
             cs_index_pass.set_bind_group(0, &self.cs_bind_groups[0], &[]);
             cs_index_pass.dispatch_workgroups(
-                self.resolution / 8,
-                self.resolution / 8,
-                self.resolution / 8,
+                workgroups_per_axis,
+                workgroups_per_axis,
+                workgroups_per_axis,
             );
         }
 
@@ -678,16 +2232,44 @@ impl State {
             cs_pass.set_pipeline(&self.cs_pipelines[1]);
             cs_pass.set_bind_group(0, &self.cs_bind_groups[1], &[]);
             cs_pass.dispatch_workgroups(
-                self.resolution / 8,
-                self.resolution / 8,
-                self.resolution / 8,
+                workgroups_per_axis,
+                workgroups_per_axis,
+                workgroups_per_axis,
             );
         }
 
-        // render pass
+        // shadow pass: render the instanced surface from the light's point
+        // of view into the shadow map before the main color pass samples it
+        if self.shadow_enabled {
+            let mut shadow_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Shadow Pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.shadow_texture_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+            shadow_pass.set_pipeline(&self.shadow_pipeline);
+            shadow_pass.set_bind_group(0, &self.shadow_bind_group, &[]);
+            shadow_pass.set_vertex_buffer(0, self.cs_vertex_buffers[1].slice(..));
+            shadow_pass.set_vertex_buffer(1, self.cs_vertex_buffers[2].slice(..));
+            shadow_pass.set_vertex_buffer(2, self.cs_vertex_buffers[3].slice(..));
+            shadow_pass.set_vertex_buffer(3, self.instance_buffer.slice(..));
+            shadow_pass.set_index_buffer(self.cs_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            shadow_pass.draw_indexed(0..self.index_count, 0, 0..self.instance_count);
+        }
+
+        // render pass: draws into the intermediate `scene_color_view`
+        // instead of `view` directly, so the post-process chain below has a
+        // sampleable texture to filter before the final blit lands on `view`.
         {
-            let color_attach = ws::create_color_attachment(&view);
-            let msaa_attach = ws::create_msaa_color_attachment(&view, &self.msaa_texture_view);
+            let color_attach = ws::create_color_attachment(&self.scene_color_view);
+            let msaa_attach =
+                ws::create_msaa_color_attachment(&self.scene_color_view, &self.msaa_texture_view);
             let color_attachment = if self.init.sample_count == 1 {
                 color_attach
             } else {
@@ -701,50 +2283,107 @@ impl State {
                 depth_stencil_attachment: Some(depth_attachment),
             });
 
-            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_pipeline(if self.textured_mode {
+                &self.textured_pipeline
+            } else {
+                &self.pipeline
+            });
             render_pass.set_vertex_buffer(0, self.cs_vertex_buffers[1].slice(..));
             render_pass.set_vertex_buffer(1, self.cs_vertex_buffers[2].slice(..));
             render_pass.set_vertex_buffer(2, self.cs_vertex_buffers[3].slice(..));
+            render_pass.set_vertex_buffer(3, self.instance_buffer.slice(..));
             render_pass.set_index_buffer(self.cs_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
             render_pass.set_bind_group(0, &self.uniform_bind_groups[0], &[]);
             render_pass.set_bind_group(1, &self.uniform_bind_groups[1], &[]);
-            render_pass.draw_indexed(0..self.index_count, 0, 0..1);
-        }
-        self.fps_counter.print_fps(5);
-        self.init.queue.submit(iter::once(encoder.finish()));
-        output.present();
+            if self.textured_mode {
+                render_pass.set_bind_group(2, &self.texture_bind_group, &[]);
+                render_pass.set_bind_group(3, &self.shadow_sample_bind_group, &[]);
+            } else {
+                render_pass.set_bind_group(2, &self.shadow_sample_bind_group, &[]);
+            }
+            render_pass.draw_indexed(0..self.index_count, 0, 0..self.instance_count);
 
-        Ok(())
-    }
-}
+            self.render_hud(&mut render_pass);
+        }
 
-fn main() {
-    let mut sample_count = 1u32;
-    let mut resolution = 192u32;
-    let mut colormap_name = "jet";
+        // post-process chain: each pass renders a fullscreen triangle into
+        // its own ping-pong target, sampling the previous stage's texture
+        // through its bind group; empty chain means this loop is a no-op and
+        // the blit below reads straight from `scene_color_view`.
+        for pass in &self.postprocess_passes {
+            let mut pp_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Post-process Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &pass.color_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+            pp_pass.set_pipeline(&pass.pipeline);
+            pp_pass.set_bind_group(0, &pass.bind_group, &[]);
+            pp_pass.draw(0..3, 0..1);
+        }
 
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() > 1 {
-        sample_count = args[1].parse::<u32>().unwrap();
-    }
-    if args.len() > 2 {
-        resolution = args[2].parse::<u32>().unwrap();
-    }
-    if args.len() > 3 {
-        colormap_name = &args[3];
+        // final blit: lands the chain's last texture (or `scene_color_view`
+        // when the chain is empty) onto the view this frame actually needs
+        // to present/capture, since that view itself isn't sampleable.
+        {
+            let mut blit_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Post-process Blit Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+            blit_pass.set_pipeline(&self.blit_pipeline);
+            blit_pass.set_bind_group(0, &self.blit_bind_group, &[]);
+            blit_pass.draw(0..3, 0..1);
+        }
     }
 
-    env_logger::init();
-    let event_loop = EventLoop::new();
-    let window = winit::window::WindowBuilder::new()
-        .build(&event_loop)
-        .unwrap();
-    window.set_title(&*format!("{}", "implict_surface"));
+    /// Draws the HUD glyph quads built by `update_hud` in their own
+    /// pipeline, inside the main color pass's existing render-pass scope so
+    /// the overlay lands on top of the surface without a separate pass.
+    fn render_hud<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.hud_vertex_count == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.hud_pipeline);
+        render_pass.set_bind_group(0, &self.hud_uniform_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.hud_texture_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.hud_vertex_buffer.slice(..));
+        render_pass.draw(0..self.hud_vertex_count, 0..1);
+    }
+}
 
-    let mut state =
-        pollster::block_on(State::new(&window, sample_count, resolution, colormap_name));
-    let render_start_time = std::time::Instant::now();
+/// Parameters read from the CLI on native or from the URL/canvas on web,
+/// shared so `main()`'s two platform entry points stay in lock-step.
+struct SurfaceParams {
+    sample_count: u32,
+    resolution: u32,
+    colormap_name: String,
+    // `--capture <dir> <frame_count>`: render `frame_count` frames to
+    // offscreen textures and write them as PNGs under `dir` instead of
+    // opening an interactive window, for turntable animations on a
+    // headless CI box. Native-only: there's no filesystem to write to
+    // from wasm32-unknown-unknown.
+    capture: Option<(std::path::PathBuf, u32)>,
+}
 
+/// Drives `window`'s events into `state` until the user closes it or the
+/// browser tab navigates away. Shared between the native and wasm32 entry
+/// points so the input/resize/redraw handling can't drift between them.
+fn run_event_loop(event_loop: EventLoop<()>, window: Window, mut state: State) -> ! {
+    let render_start_time = Instant::now();
     event_loop.run(move |event, _, control_flow| match event {
         Event::WindowEvent {
             ref event,
@@ -773,7 +2412,7 @@ fn main() {
             }
         }
         Event::RedrawRequested(_) => {
-            let now = std::time::Instant::now();
+            let now = Instant::now();
             let dt = now - render_start_time;
             state.update(dt);
 
@@ -789,4 +2428,155 @@ fn main() {
         }
         _ => {}
     });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_native_params() -> SurfaceParams {
+    let mut params = SurfaceParams {
+        sample_count: 1,
+        resolution: 192,
+        colormap_name: "jet".to_string(),
+        capture: None,
+    };
+
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() > 1 {
+        params.sample_count = args[1].parse::<u32>().unwrap();
+    }
+    if args.len() > 2 {
+        params.resolution = args[2].parse::<u32>().unwrap();
+    }
+    if args.len() > 3 {
+        params.colormap_name = args[3].clone();
+    }
+    if args.len() > 4 && args[4] == "--capture" {
+        let dir = args.get(5).cloned().unwrap_or_else(|| "frames".to_string());
+        let frame_count = args.get(6).and_then(|s| s.parse::<u32>().ok()).unwrap_or(60);
+        params.capture = Some((std::path::PathBuf::from(dir), frame_count));
+    }
+    params
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    env_logger::init();
+    let params = parse_native_params();
+
+    let event_loop = EventLoop::new();
+    let window = winit::window::WindowBuilder::new()
+        .build(&event_loop)
+        .unwrap();
+    window.set_title(&*format!("{}", "implict_surface"));
+
+    let mut state = pollster::block_on(State::new(
+        &window,
+        params.sample_count,
+        params.resolution,
+        &params.colormap_name,
+        params.capture.clone(),
+    ));
+
+    if let Some((_, frame_count)) = params.capture {
+        // Headless: drive the render loop directly instead of waiting on
+        // window events, since nothing will ever present to the window.
+        let render_start_time = Instant::now();
+        for _ in 0..frame_count {
+            let dt = render_start_time.elapsed();
+            state.update(dt);
+            state.render().expect("offscreen capture render failed");
+        }
+        println!("wrote {} captured frame(s) to {:?}", frame_count, state.capture_dir);
+        return;
+    }
+
+    run_event_loop(event_loop, window, state);
+}
+
+/// Reads `?sample-count=&resolution=&colormap=` from the page URL, falling
+/// back to `data-sample-count`/`data-resolution`/`data-colormap` on the
+/// canvas's host element, then the same defaults `parse_native_params`
+/// uses, since a web embed has neither argv nor a `--capture` flag.
+#[cfg(target_arch = "wasm32")]
+fn parse_web_params(host: &web_sys::HtmlElement) -> SurfaceParams {
+    let mut params = SurfaceParams {
+        sample_count: 1,
+        resolution: 192,
+        colormap_name: "jet".to_string(),
+        capture: None,
+    };
+
+    let query: HashMap<String, String> = web_sys::window()
+        .and_then(|win| win.location().search().ok())
+        .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok())
+        .map(|search_params| {
+            search_params
+                .entries()
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let pair = js_sys::Array::from(&entry);
+                    Some((pair.get(0).as_string()?, pair.get(1).as_string()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let read = |key: &str| {
+        query
+            .get(key)
+            .cloned()
+            .or_else(|| host.get_attribute(&format!("data-{}", key)))
+    };
+
+    if let Some(v) = read("sample-count").and_then(|s| s.parse().ok()) {
+        params.sample_count = v;
+    }
+    if let Some(v) = read("resolution").and_then(|s| s.parse().ok()) {
+        params.resolution = v;
+    }
+    if let Some(v) = read("colormap") {
+        params.colormap_name = v;
+    }
+    params
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    console_log::init_with_level(log::Level::Warn).expect("could not initialize console_log");
+    wasm_bindgen_futures::spawn_local(run_wasm());
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn run_wasm() {
+    use wasm_bindgen::JsCast;
+    use winit::platform::web::WindowExtWebSys;
+
+    let event_loop = EventLoop::new();
+    let window = winit::window::WindowBuilder::new()
+        .build(&event_loop)
+        .unwrap();
+    window.set_title("implicit_surface");
+
+    let canvas = window.canvas();
+    let host: web_sys::HtmlElement = web_sys::window()
+        .and_then(|win| win.document())
+        .and_then(|doc| doc.body())
+        .expect("no <body> to attach the canvas to")
+        .dyn_into()
+        .expect("document.body isn't an HtmlElement");
+    host.append_child(&canvas)
+        .expect("couldn't append the canvas to the DOM");
+
+    let params = parse_web_params(&host);
+    let state = State::new(
+        &window,
+        params.sample_count,
+        params.resolution,
+        &params.colormap_name,
+        None,
+    )
+    .await;
+
+    run_event_loop(event_loop, window, state);
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 use bytemuck::cast_slice;
 use cgmath::{Matrix, Matrix4, SquareMatrix};
 use rand::{distributions::Uniform, Rng};
+use rayon::prelude::*;
 use std::iter;
 use wgpu::{util::DeviceExt, VertexBufferLayout};
 use winit::{
@@ -11,6 +12,439 @@ use winit::{
 use wgpu_simplified as ws;
 use app2_dockercompose_rust_wgpu_marchingcubes::{colormap, marching_cubes_table};
 
+// near/far planes matching the perspective matrix `ws::create_vp_mat` builds
+// internally; only needed here to linearize depth for VIEW_MODE_DEPTH.
+const NEAR: f32 = 0.1;
+const FAR: f32 = 100.0;
+
+// render modes cycled by `V`: lit shading, raw isosurface normals, and
+// linearized depth, all driven by the same `view_mode` field threaded into
+// the material uniform so metaball_frag.wgsl can branch on it.
+const VIEW_MODE_LIT: u32 = 0;
+const VIEW_MODE_NORMAL: u32 = 1;
+const VIEW_MODE_DEPTH: u32 = 2;
+const VIEW_MODE_COUNT: u32 = 3;
+
+/// A GPU resource a [`RenderNode`] reads or writes. [`RenderGraph::execute`]
+/// infers pass ordering from these instead of the caller hand-sequencing
+/// `record_frame`: a node that reads a handle must run after every node
+/// that writes it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ResourceHandle {
+    ValueField,
+    IsosurfaceMesh,
+    SceneColor,
+    ColorTarget,
+}
+
+/// Resources a [`RenderNode`] needs to record its pass, borrowed from
+/// `State` rather than the whole struct so a node only sees what its own
+/// pass touches.
+struct GraphContext<'a> {
+    state: &'a State,
+    view: &'a wgpu::TextureView,
+}
+
+/// One pass in the metaball render graph. Declares the resources it reads
+/// and writes so [`RenderGraph`] can order passes and group independent
+/// ones instead of the sequence being hardcoded into `record_frame`.
+trait RenderNode: Sync {
+    fn reads(&self) -> &'static [ResourceHandle];
+    fn writes(&self) -> &'static [ResourceHandle];
+    fn record(&self, ctx: &GraphContext, encoder: &mut wgpu::CommandEncoder);
+}
+
+/// Computes the scalar metaball field into `cs_value_buffer` for every grid
+/// cell. First stage of the pipeline; writes [`ResourceHandle::ValueField`].
+struct ValueFieldNode;
+
+impl RenderNode for ValueFieldNode {
+    fn reads(&self) -> &'static [ResourceHandle] {
+        &[]
+    }
+
+    fn writes(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::ValueField]
+    }
+
+    fn record(&self, ctx: &GraphContext, encoder: &mut wgpu::CommandEncoder) {
+        let state = ctx.state;
+        let mut cs_index_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Compute value Pass"),
+        });
+        cs_index_pass.set_pipeline(&state.cs_pipelines[0]);
+        cs_index_pass.set_bind_group(0, &state.cs_bind_groups[0], &[]);
+        cs_index_pass.dispatch_workgroups(
+            state.resolution / 4,
+            state.resolution / 4,
+            state.resolution / 4,
+        );
+    }
+}
+
+/// Extracts the marching-cubes isosurface (positions/normals/colors/indices)
+/// from the value field computed by [`ValueFieldNode`]. Reads
+/// [`ResourceHandle::ValueField`], writes [`ResourceHandle::IsosurfaceMesh`].
+struct IsosurfaceNode;
+
+impl RenderNode for IsosurfaceNode {
+    fn reads(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::ValueField]
+    }
+
+    fn writes(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::IsosurfaceMesh]
+    }
+
+    fn record(&self, ctx: &GraphContext, encoder: &mut wgpu::CommandEncoder) {
+        let state = ctx.state;
+        let mut cs_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Compute Pass"),
+        });
+        cs_pass.set_pipeline(&state.cs_pipelines[1]);
+        cs_pass.set_bind_group(0, &state.cs_bind_groups[1], &[]);
+        cs_pass.dispatch_workgroups(
+            state.resolution / 4,
+            state.resolution / 4,
+            state.resolution / 4,
+        );
+    }
+}
+
+/// Draws the extracted isosurface (plus the optional debug-sphere overlay)
+/// into `scene_color_view` rather than `ctx.view` directly, so the
+/// post-process chain has an intermediate texture to filter before the
+/// final blit. Reads [`ResourceHandle::IsosurfaceMesh`], writes
+/// [`ResourceHandle::SceneColor`].
+struct MainRenderNode;
+
+impl RenderNode for MainRenderNode {
+    fn reads(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::IsosurfaceMesh]
+    }
+
+    fn writes(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::SceneColor]
+    }
+
+    fn record(&self, ctx: &GraphContext, encoder: &mut wgpu::CommandEncoder) {
+        let state = ctx.state;
+        let color_attach = ws::create_color_attachment(&state.scene_color_view);
+        let msaa_attach =
+            ws::create_msaa_color_attachment(&state.scene_color_view, &state.msaa_texture_view);
+        let color_attachment = if state.init.sample_count == 1 {
+            color_attach
+        } else {
+            msaa_attach
+        };
+        let depth_attachment = ws::create_depth_stencil_attachment(&state.depth_texture_view);
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(color_attachment)],
+            depth_stencil_attachment: Some(depth_attachment),
+        });
+
+        render_pass.set_pipeline(&state.pipeline);
+        render_pass.set_vertex_buffer(0, state.cs_vertex_buffers[1].slice(..));
+        render_pass.set_vertex_buffer(1, state.cs_vertex_buffers[2].slice(..));
+        render_pass.set_vertex_buffer(2, state.cs_vertex_buffers[3].slice(..));
+        render_pass.set_index_buffer(state.cs_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.set_bind_group(0, &state.uniform_bind_groups[0], &[]);
+        render_pass.set_bind_group(1, &state.uniform_bind_groups[1], &[]);
+        render_pass.draw_indexed(0..state.index_count, 0, 0..1);
+
+        if state.show_metaball_spheres {
+            render_pass.set_pipeline(&state.sphere_pipeline);
+            render_pass.set_vertex_buffer(0, state.sphere_vertex_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, state.sphere_instance_buffer.slice(..));
+            render_pass
+                .set_index_buffer(state.sphere_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.set_bind_group(0, &state.uniform_bind_groups[0], &[]);
+            render_pass.draw_indexed(0..state.sphere_index_count, 0, 0..state.metaballs_count);
+        }
+
+        state.render_hud(&mut render_pass);
+    }
+}
+
+/// Runs the post-process chain (see `POSTPROCESS_PRESET_PATH` below) over
+/// `scene_color_view` and blits whatever comes out last onto `ctx.view`.
+/// Reads [`ResourceHandle::SceneColor`], writes
+/// [`ResourceHandle::ColorTarget`]; the last stage of the pipeline. An empty
+/// chain (no preset file, or one that can't be read) just blits
+/// `scene_color_view` through unmodified.
+struct PostProcessChainNode;
+
+impl RenderNode for PostProcessChainNode {
+    fn reads(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::SceneColor]
+    }
+
+    fn writes(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::ColorTarget]
+    }
+
+    fn record(&self, ctx: &GraphContext, encoder: &mut wgpu::CommandEncoder) {
+        let state = ctx.state;
+
+        for pass in &state.postprocess_passes {
+            let color_attachment = ws::create_color_attachment(&pass.color_view);
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Post-process Pass"),
+                color_attachments: &[Some(color_attachment)],
+                depth_stencil_attachment: None,
+            });
+            render_pass.set_pipeline(&pass.pipeline);
+            render_pass.set_bind_group(0, &pass.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        let blit_attachment = ws::create_color_attachment(ctx.view);
+        let mut blit_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Post-process Blit Pass"),
+            color_attachments: &[Some(blit_attachment)],
+            depth_stencil_attachment: None,
+        });
+        blit_pass.set_pipeline(&state.blit_pipeline);
+        blit_pass.set_bind_group(0, &state.blit_bind_group, &[]);
+        blit_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Small declarative render graph replacing the hardcoded
+/// value → isosurface → render → post-process sequence. Nodes declare the
+/// [`ResourceHandle`]s they read/write; [`RenderGraph::levels`] derives a
+/// dependency order from that and groups nodes with no edge between them
+/// so their command recording (not their GPU execution - passes within one
+/// level still submit in the graph's node order) can run off the main
+/// thread with `rayon`. The current four nodes form a strict chain, but an
+/// extension (e.g. an independent readback pass also reading `ColorTarget`)
+/// can be added as a fifth node without touching `record_frame`.
+struct RenderGraph {
+    nodes: Vec<Box<dyn RenderNode>>,
+}
+
+impl RenderGraph {
+    fn new() -> Self {
+        Self {
+            nodes: vec![
+                Box::new(ValueFieldNode),
+                Box::new(IsosurfaceNode),
+                Box::new(MainRenderNode),
+                Box::new(PostProcessChainNode),
+            ],
+        }
+    }
+
+    /// Topologically sorts `nodes` into levels: node `i` depends on node
+    /// `j` (and so lands in a later level) whenever `i` reads a handle `j`
+    /// writes. Nodes within a level share no such edge and are recorded in
+    /// parallel.
+    fn levels(&self) -> Vec<Vec<usize>> {
+        let depends_on = |i: usize, j: usize| {
+            self.nodes[i]
+                .reads()
+                .iter()
+                .any(|h| self.nodes[j].writes().contains(h))
+        };
+
+        let mut placed = vec![false; self.nodes.len()];
+        let mut levels = Vec::new();
+        while placed.iter().any(|&p| !p) {
+            let level: Vec<usize> = (0..self.nodes.len())
+                .filter(|&i| {
+                    !placed[i]
+                        && (0..self.nodes.len()).all(|j| j == i || placed[j] || !depends_on(i, j))
+                })
+                .collect();
+            assert!(!level.is_empty(), "render graph has a dependency cycle");
+            for &i in &level {
+                placed[i] = true;
+            }
+            levels.push(level);
+        }
+        levels
+    }
+
+    /// Records every node into its own command buffer (nodes sharing a
+    /// level run concurrently via `rayon`) and returns them in submission
+    /// order, ready for `queue.submit`.
+    fn execute(&self, ctx: &GraphContext) -> Vec<wgpu::CommandBuffer> {
+        let mut buffers = Vec::with_capacity(self.nodes.len());
+        for level in self.levels() {
+            let mut recorded: Vec<(usize, wgpu::CommandBuffer)> = level
+                .par_iter()
+                .map(|&i| {
+                    let mut encoder =
+                        ctx.state
+                            .init
+                            .device
+                            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                                label: Some("Render Graph Node Encoder"),
+                            });
+                    self.nodes[i].record(ctx, &mut encoder);
+                    (i, encoder.finish())
+                })
+                .collect();
+            recorded.sort_by_key(|(i, _)| *i);
+            buffers.extend(recorded.into_iter().map(|(_, buf)| buf));
+        }
+        buffers
+    }
+}
+
+// HUD: a monospace bitmap font atlas covering ASCII 32..128 laid out in a
+// 16x8 grid, rendered as one textured quad per character.
+const HUD_FONT_ATLAS_PATH: &str = "examples/ch03/font_atlas.png";
+const HUD_GLYPH_COLS: u32 = 16;
+const HUD_GLYPH_ROWS: u32 = 8;
+const HUD_GLYPH_PX: f32 = 16.0;
+const HUD_MAX_CHARS: u32 = 256;
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct HudVertex {
+    pos: [f32; 2],
+    uv: [f32; 2],
+}
+
+/// Lays out `text` as one quad per character on a pixel grid starting at
+/// `origin` (top-left, Y down), each sampling its glyph cell out of the font
+/// atlas. `\n` starts a new line instead of consuming a glyph slot, so the
+/// FPS/resolution/isolevel/colormap/strength lines in `update_hud` can be
+/// built as a single string.
+fn build_hud_vertices(text: &str, origin: (f32, f32)) -> Vec<HudVertex> {
+    let mut vertices = Vec::with_capacity(text.len() * 6);
+    let cell_w = 1.0 / HUD_GLYPH_COLS as f32;
+    let cell_h = 1.0 / HUD_GLYPH_ROWS as f32;
+
+    let mut col = 0u32;
+    let mut row = 0u32;
+    let mut quad_count = 0u32;
+
+    for ch in text.chars() {
+        if quad_count >= HUD_MAX_CHARS {
+            break;
+        }
+        if ch == '\n' {
+            col = 0;
+            row += 1;
+            continue;
+        }
+        let code = ch as u32;
+        if !(32..128).contains(&code) {
+            continue;
+        }
+        let glyph = code - 32;
+        let atlas_col = (glyph % HUD_GLYPH_COLS) as f32;
+        let atlas_row = (glyph / HUD_GLYPH_COLS) as f32;
+        let u0 = atlas_col * cell_w;
+        let v0 = atlas_row * cell_h;
+        let u1 = u0 + cell_w;
+        let v1 = v0 + cell_h;
+
+        let x0 = origin.0 + col as f32 * HUD_GLYPH_PX;
+        let x1 = x0 + HUD_GLYPH_PX;
+        let y0 = origin.1 + row as f32 * HUD_GLYPH_PX;
+        let y1 = y0 + HUD_GLYPH_PX;
+
+        vertices.push(HudVertex { pos: [x0, y0], uv: [u0, v0] });
+        vertices.push(HudVertex { pos: [x1, y0], uv: [u1, v0] });
+        vertices.push(HudVertex { pos: [x0, y1], uv: [u0, v1] });
+        vertices.push(HudVertex { pos: [x0, y1], uv: [u0, v1] });
+        vertices.push(HudVertex { pos: [x1, y0], uv: [u1, v0] });
+        vertices.push(HudVertex { pos: [x1, y1], uv: [u1, v1] });
+
+        col += 1;
+        quad_count += 1;
+    }
+
+    vertices
+}
+
+// Post-process chain: the main pass now renders into an intermediate
+// `scene_color_view` instead of straight into the swapchain/capture view,
+// then an ordered list of fullscreen filter passes declared by
+// POSTPROCESS_PRESET_PATH filters it stage by stage before a final blit
+// lands the result on the view `record_frame` was actually asked to draw
+// into. A missing preset file means an empty chain: the scene still goes
+// through the intermediate texture but blits straight through unmodified.
+const POSTPROCESS_PRESET_PATH: &str = "examples/ch03/postprocess.preset";
+
+/// Parses one `<fragment-shader-path> <output-scale> <filter>` line per
+/// pass (`filter` is "nearest" or "linear", defaulting to linear); `#`
+/// starts a comment. Returns an empty chain when the file can't be read, so
+/// the effect list stays opt-in rather than a hard requirement.
+fn load_postprocess_preset(path: &str) -> Vec<(String, f32, wgpu::FilterMode)> {
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    source
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let shader_path = parts.next()?.to_string();
+            let scale = parts
+                .next()
+                .and_then(|s| s.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let filter = match parts.next() {
+                Some("nearest") => wgpu::FilterMode::Nearest,
+                _ => wgpu::FilterMode::Linear,
+            };
+            Some((shader_path, scale, filter))
+        })
+        .collect()
+}
+
+/// Creates a `TEXTURE_BINDING | RENDER_ATTACHMENT` target for one
+/// post-process stage; dimensions are clamped to at least 1px so a
+/// sub-1.0 `scale` chain can't collapse to a zero-sized texture.
+fn create_postprocess_target(
+    device: &wgpu::Device,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    label: &str,
+) -> wgpu::TextureView {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// One fullscreen filter stage in the post-process chain. Every stage
+/// shares the `fullscreen_vert.wgsl` vertex shader (none of them use a
+/// vertex buffer, just a 3-vertex fullscreen triangle). Besides the
+/// previous stage's texture, each pass also binds `uniform_buffer` -
+/// resolution, elapsed time, and the `isolevel`/`scale`/colormap params
+/// already tracked on `State` - so a preset shader (bloom, tonemap, CRT
+/// curvature, ...) can react to the same parameters steering the
+/// isosurface itself instead of only seeing raw pixels.
+struct PostProcessPass {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    scale: f32,
+    color_view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+}
+
 fn create_color_data(colormap_name: &str) -> Vec<[f32; 4]> {
     let cdata = colormap::colormap_data(colormap_name);
     let mut data: Vec<[f32; 4]> = vec![];
@@ -20,6 +454,41 @@ fn create_color_data(colormap_name: &str) -> Vec<[f32; 4]> {
     data
 }
 
+/// A unit UV-sphere (radius 1, centered at the origin), instanced once per
+/// metaball center in `MainRenderNode` so users can see where the
+/// `metaballs_count` `MetaballPosition` emitters actually are versus the reconstructed
+/// isosurface. Positions are `[f32; 4]` to match the `array_stride: 16`
+/// layout every other vertex buffer in this file already uses.
+fn build_unit_sphere(stacks: u32, slices: u32) -> (Vec<[f32; 4]>, Vec<u32>) {
+    let mut vertices = vec![];
+    for stack in 0..=stacks {
+        let phi = std::f32::consts::PI * stack as f32 / stacks as f32;
+        for slice in 0..=slices {
+            let theta = 2.0 * std::f32::consts::PI * slice as f32 / slices as f32;
+            let x = phi.sin() * theta.cos();
+            let y = phi.cos();
+            let z = phi.sin() * theta.sin();
+            vertices.push([x, y, z, 1.0]);
+        }
+    }
+
+    let mut indices = vec![];
+    let ring = slices + 1;
+    for stack in 0..stacks {
+        for slice in 0..slices {
+            let a = stack * ring + slice;
+            let b = a + ring;
+            indices.push(a);
+            indices.push(b);
+            indices.push(a + 1);
+            indices.push(a + 1);
+            indices.push(b);
+            indices.push(b + 1);
+        }
+    }
+    (vertices, indices)
+}
+
 #[derive(Clone, Debug)]
 struct MetaballPosition {
     x: f32,
@@ -43,6 +512,16 @@ struct State {
     cs_uniform_buffers: Vec<wgpu::Buffer>,
     cs_bind_groups: Vec<wgpu::BindGroup>,
 
+    // kept around (rather than consumed at construction) so `reallocate`
+    // can rebuild the compute bind groups against buffers of a new size
+    // without re-deriving a layout or re-uploading the static tables.
+    cs_value_bind_group_layout: wgpu::BindGroupLayout,
+    cs_bind_group_layout: wgpu::BindGroupLayout,
+    frag_bind_group_layout: wgpu::BindGroupLayout,
+    cs_table_buffer: wgpu::Buffer,
+    cs_colormap_buffer: wgpu::Buffer,
+    cdata: Vec<[f32; 4]>,
+
     view_mat: Matrix4<f32>,
     project_mat: Matrix4<f32>,
     msaa_texture_view: wgpu::TextureView,
@@ -54,18 +533,75 @@ struct State {
 
     colormap_direction: u32,
     colormap_reverse: u32,
+    colormap_name: String,
     isolevel: f32,
     scale: f32,
 
+    // cycled by `V` between lit shading, raw normals, and linearized depth;
+    // written into material_uniform_buffer's trailing u32 (see `new`).
+    view_mode: u32,
+
     metaball_positions: Vec<MetaballPosition>,
     metaball_array: Vec<f32>,
     strength: f32,
     strength_target: f32,
     subtract: f32,
     subtract_target: f32,
+    // `true` stops `step_simulation`'s every-5-seconds reshuffle of
+    // `strength_target`/`subtract_target`, toggled by `input()` so a user
+    // can hold the surface steady while exploring isolevel/colormap.
+    freeze_targets: bool,
     start: std::time::Instant,
     t0: std::time::Instant,
+    headless_sim_time: f32,
     fps_counter: ws::FpsCounter,
+    // Smoothed separately from `fps_counter` (which only prints, it has no
+    // getter) so `update_hud` has a number to show on the overlay.
+    fps: f32,
+    last_update_instant: std::time::Instant,
+
+    // debug overlay: a unit sphere instanced once per metaball center, at
+    // its current radius, toggled with M so the sim can be checked without
+    // a CPU readback of the marching-cubes output.
+    sphere_pipeline: wgpu::RenderPipeline,
+    sphere_vertex_buffer: wgpu::Buffer,
+    sphere_index_buffer: wgpu::Buffer,
+    sphere_index_count: u32,
+    sphere_instance_buffer: wgpu::Buffer,
+    show_metaball_spheres: bool,
+
+    // one Blinn-Phong point light per metaball, rewritten every frame from
+    // the same positions driving the isosurface; light_colors is the fixed
+    // per-light tint and light_array is the scratch buffer it's packed into.
+    light_storage_buffer: wgpu::Buffer,
+    light_colors: Vec<[f32; 3]>,
+    light_array: Vec<f32>,
+
+    // declarative replacement for the old hand-sequenced
+    // value -> isosurface -> render pass order; see `RenderGraph`.
+    render_graph: RenderGraph,
+
+    // Post-process chain: the scene renders into `scene_color_view`, each
+    // `postprocess_passes` entry filters the previous stage's texture, and
+    // `blit_*` copies whichever texture ends up last onto the view
+    // `record_frame` was asked to draw into. See POSTPROCESS_PRESET_PATH.
+    scene_color_view: wgpu::TextureView,
+    postprocess_passes: Vec<PostProcessPass>,
+    blit_pipeline: wgpu::RenderPipeline,
+    blit_bind_group_layout: wgpu::BindGroupLayout,
+    blit_sampler: wgpu::Sampler,
+    blit_bind_group: wgpu::BindGroup,
+
+    // HUD overlay: FPS/resolution/isolevel/colormap/strength rendered as
+    // textured glyph quads in their own pipeline, drawn inside the main
+    // color pass (see `render_hud`) so it lands on the scene before the
+    // post-process chain runs.
+    hud_pipeline: wgpu::RenderPipeline,
+    hud_uniform_bind_group: wgpu::BindGroup,
+    hud_texture_bind_group: wgpu::BindGroup,
+    hud_uniform_buffer: wgpu::Buffer,
+    hud_vertex_buffer: wgpu::Buffer,
+    hud_vertex_count: u32,
 }
 
 impl State {
@@ -90,9 +626,15 @@ impl State {
         let vs_shader = init
             .device
             .create_shader_module(wgpu::include_wgsl!("../ch01/shader_vert.wgsl"));
-        let fs_shader = init
-            .device
-            .create_shader_module(wgpu::include_wgsl!("../ch01/shader_frag.wgsl"));
+        // metaball.rs used to share ch01's single-directional-light
+        // shader_frag.wgsl with implicit_surface.rs. The point-light model
+        // below needs its own bind group layout (a storage buffer instead of
+        // a second uniform), so it gets its own fragment shader rather than
+        // changing the layout implicit_surface.rs still relies on.
+        let fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Metaball Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("metaball_frag.wgsl").into()),
+        });
         let cs_value = init
             .device
             .create_shader_module(wgpu::include_wgsl!("metaball_value.wgsl"));
@@ -107,7 +649,7 @@ impl State {
         let camera_position = (2.0, 2.0, 3.0).into();
         let look_direction = (0.0, 0.0, 0.0).into();
         let up_direction = cgmath::Vector3::unit_y();
-        let light_direction = [-0.5f32, -0.5, -0.5];
+        let cdata = create_color_data(colormap_name);
 
         let (view_mat, project_mat, vp_mat) = ws::create_vp_mat(
             camera_position,
@@ -141,35 +683,25 @@ impl State {
             cast_slice(normal_mat.as_ref() as &[f32; 16]),
         );
 
-        // create light uniform buffer. here we set eye_position = camera_position
-        let light_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Light Uniform Buffer"),
-            size: 48,
+        // eye uniform buffer: just the camera position now, since the
+        // lights themselves moved out into light_storage_buffer below
+        let eye_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Eye Uniform Buffer"),
+            size: 16,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
-
         let eye_position: &[f32; 3] = camera_position.as_ref();
-        init.queue.write_buffer(
-            &light_uniform_buffer,
-            0,
-            cast_slice(light_direction.as_ref()),
-        );
         init.queue
-            .write_buffer(&light_uniform_buffer, 16, cast_slice(eye_position));
-
-        // set specular light color to white
-        let specular_color: [f32; 3] = [1.0, 1.0, 1.0];
-        init.queue.write_buffer(
-            &light_uniform_buffer,
-            32,
-            cast_slice(specular_color.as_ref()),
-        );
+            .write_buffer(&eye_uniform_buffer, 0, cast_slice(eye_position));
 
-        // material uniform buffer
+        // material uniform buffer: the material vec4, plus a second vec4
+        // carrying view_mode (driving the normal/depth debug modes) and the
+        // near/far planes the depth mode linearizes against, matching what
+        // a WGSL struct with those two vec4 members would lay out as.
         let material_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Material Uniform Buffer"),
-            size: 16,
+            size: 32,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
@@ -178,6 +710,34 @@ impl State {
         let material = [0.1f32, 0.7, 0.4, 30.0];
         init.queue
             .write_buffer(&material_uniform_buffer, 0, cast_slice(material.as_ref()));
+        let view_mode = VIEW_MODE_LIT;
+        init.queue.write_buffer(
+            &material_uniform_buffer,
+            16,
+            cast_slice(&[view_mode, NEAR.to_bits(), FAR.to_bits(), 0u32]),
+        );
+
+        // point lights: one per metaball, so the blobby surface is shaded by
+        // its own moving emitters (Blinn-Phong, accumulated in
+        // metaball_frag.wgsl over every entry of this storage buffer).
+        // Layout per light (32 bytes): position: vec3<f32>, intensity: f32,
+        // color: vec3<f32>, falloff: f32. Position and intensity are
+        // rewritten every frame in `update` from the same ball positions the
+        // physics sim already tracks; color is fixed at creation time, one
+        // colormap sample per ball, so each light reads like a tinted ember.
+        let light_colors: Vec<[f32; 3]> = (0..metaballs_count as usize)
+            .map(|i| {
+                let c = &cdata[i * cdata.len() / metaballs_count as usize];
+                [c[0], c[1], c[2]]
+            })
+            .collect();
+        let light_array = vec![0f32; 8 * metaballs_count as usize];
+        let light_storage_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Point Light Storage Buffer"),
+            size: 32 * metaballs_count as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
 
         // uniform bind group for vertex shader
         let (vert_bind_group_layout, vert_bind_group) = ws::create_bind_group(
@@ -187,12 +747,22 @@ impl State {
         );
 
         // uniform bind group for fragment shader
-        let (frag_bind_group_layout, frag_bind_group) = ws::create_bind_group(
+        let (frag_bind_group_layout, frag_bind_group) = ws::create_bind_group_storage(
             &init.device,
-            vec![wgpu::ShaderStages::FRAGMENT, wgpu::ShaderStages::FRAGMENT],
+            vec![
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::ShaderStages::FRAGMENT,
+            ],
+            vec![
+                wgpu::BufferBindingType::Uniform,                    // eye position
+                wgpu::BufferBindingType::Uniform,                    // material
+                wgpu::BufferBindingType::Storage { read_only: true }, // point lights
+            ],
             &[
-                light_uniform_buffer.as_entire_binding(),
+                eye_uniform_buffer.as_entire_binding(),
                 material_uniform_buffer.as_entire_binding(),
+                light_storage_buffer.as_entire_binding(),
             ],
         );
 
@@ -234,6 +804,69 @@ impl State {
         let msaa_texture_view = ws::create_msaa_texture_view(&init);
         let depth_texture_view = ws::create_depth_view(&init);
 
+        // debug-sphere overlay: one unit sphere mesh, instanced per metaball
+        // with its world position and current radius in a second,
+        // VertexStepMode::Instance vertex buffer (mirrors the learn-wgpu
+        // instancing tutorial's per-instance transform buffer).
+        let (sphere_positions, sphere_indices) = build_unit_sphere(8, 16);
+        let sphere_vertex_buffer =
+            init.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Metaball Debug Sphere Vertex Buffer"),
+                    contents: cast_slice(&sphere_positions),
+                    usage: wgpu::BufferUsages::VERTEX,
+                });
+        let sphere_index_buffer =
+            init.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Metaball Debug Sphere Index Buffer"),
+                    contents: cast_slice(&sphere_indices),
+                    usage: wgpu::BufferUsages::INDEX,
+                });
+        let sphere_index_count = sphere_indices.len() as u32;
+        let sphere_instance_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Metaball Debug Sphere Instance Buffer"),
+            size: 16 * metaballs_count as u64, // x, y, z, radius per instance
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let sphere_vs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Metaball Debug Sphere Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("sphere_debug_vert.wgsl").into()),
+        });
+        let sphere_fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Metaball Debug Sphere Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("sphere_debug_frag.wgsl").into()),
+        });
+        let sphere_vertex_buffer_layouts = [
+            VertexBufferLayout {
+                array_stride: 16,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x4], // unit sphere position
+            },
+            VertexBufferLayout {
+                array_stride: 16,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &wgpu::vertex_attr_array![1 => Float32x4], // instance x,y,z,radius
+            },
+        ];
+        let sphere_pipeline_layout =
+            init.device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Metaball Debug Sphere Pipeline Layout"),
+                    bind_group_layouts: &[&vert_bind_group_layout],
+                    push_constant_ranges: &[],
+                });
+        let mut sphere_ppl = ws::IRenderPipeline {
+            vs_shader: Some(&sphere_vs_shader),
+            fs_shader: Some(&sphere_fs_shader),
+            pipeline_layout: Some(&sphere_pipeline_layout),
+            vertex_buffer_layout: &sphere_vertex_buffer_layouts,
+            ..Default::default()
+        };
+        let sphere_pipeline = sphere_ppl.new(&init);
+
         // create compute pipeline for value
         let volume_elements = resol * resol * resol;
         let cs_value_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
@@ -383,7 +1016,6 @@ impl State {
             mapped_at_creation: false,
         });
 
-        let cdata = create_color_data(colormap_name);
         let cs_colormap_buffer =
             init.device
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -463,13 +1095,382 @@ impl State {
                 entry_point: "cs_main",
             });
 
+        // Post-process chain (see POSTPROCESS_PRESET_PATH doc comment above):
+        // the main pass renders into `scene_color_view` instead of straight
+        // into the view `record_frame` is asked to draw into.
+        let postprocess_format = init.config.format;
+        let scene_color_view = create_postprocess_target(
+            &init.device,
+            init.config.width,
+            init.config.height,
+            postprocess_format,
+            "Scene Color Target",
+        );
+
+        let fullscreen_vs_shader = init
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Fullscreen Triangle Vertex Shader"),
+                source: wgpu::ShaderSource::Wgsl(include_str!("fullscreen_vert.wgsl").into()),
+            });
+
+        let postprocess_preset = load_postprocess_preset(POSTPROCESS_PRESET_PATH);
+        let mut postprocess_passes: Vec<PostProcessPass> =
+            Vec::with_capacity(postprocess_preset.len());
+        let mut prev_width = init.config.width;
+        let mut prev_height = init.config.height;
+
+        for (index, (shader_path, scale, filter)) in postprocess_preset.iter().enumerate() {
+            let fs_source = std::fs::read_to_string(shader_path).unwrap_or_else(|err| {
+                panic!("failed to read post-process shader {shader_path:?}: {err}")
+            });
+            let fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(&format!("Post-process Pass {index} Fragment Shader")),
+                source: wgpu::ShaderSource::Wgsl(fs_source.into()),
+            });
+
+            let sampler = init.device.create_sampler(&wgpu::SamplerDescriptor {
+                address_mode_u: wgpu::AddressMode::ClampToEdge,
+                address_mode_v: wgpu::AddressMode::ClampToEdge,
+                mag_filter: *filter,
+                min_filter: *filter,
+                ..Default::default()
+            });
+
+            // resolution (vec2), time, isolevel, scale, colormap_direction,
+            // colormap_reverse, padding - matches the 16-byte-multiple sizing
+            // every other uniform buffer in this file uses.
+            let uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Post-process Pass Uniform Buffer"),
+                size: 32,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            let bind_group_layout =
+                init.device
+                    .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                        label: Some("Post-process Pass Bind Group Layout"),
+                        entries: &[
+                            wgpu::BindGroupLayoutEntry {
+                                binding: 0,
+                                visibility: wgpu::ShaderStages::FRAGMENT,
+                                ty: wgpu::BindingType::Texture {
+                                    multisampled: false,
+                                    view_dimension: wgpu::TextureViewDimension::D2,
+                                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                                },
+                                count: None,
+                            },
+                            wgpu::BindGroupLayoutEntry {
+                                binding: 1,
+                                visibility: wgpu::ShaderStages::FRAGMENT,
+                                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                                count: None,
+                            },
+                            wgpu::BindGroupLayoutEntry {
+                                binding: 2,
+                                visibility: wgpu::ShaderStages::FRAGMENT,
+                                ty: wgpu::BindingType::Buffer {
+                                    ty: wgpu::BufferBindingType::Uniform,
+                                    has_dynamic_offset: false,
+                                    min_binding_size: None,
+                                },
+                                count: None,
+                            },
+                        ],
+                    });
+            let bind_group = {
+                let source_view: &wgpu::TextureView = match postprocess_passes.last() {
+                    Some(prev) => &prev.color_view,
+                    None => &scene_color_view,
+                };
+                init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Post-process Pass Bind Group"),
+                    layout: &bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(source_view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&sampler),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: uniform_buffer.as_entire_binding(),
+                        },
+                    ],
+                })
+            };
+
+            let pipeline_layout =
+                init.device
+                    .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: Some("Post-process Pass Pipeline Layout"),
+                        bind_group_layouts: &[&bind_group_layout],
+                        push_constant_ranges: &[],
+                    });
+            let mut pp_ppl = ws::IRenderPipeline {
+                vs_shader: Some(&fullscreen_vs_shader),
+                fs_shader: Some(&fs_shader),
+                pipeline_layout: Some(&pipeline_layout),
+                vertex_buffer_layout: &[],
+                depth_format: None,
+                ..Default::default()
+            };
+            let pipeline = pp_ppl.new(&init);
+
+            let pass_width = ((prev_width as f32) * scale).round().max(1.0) as u32;
+            let pass_height = ((prev_height as f32) * scale).round().max(1.0) as u32;
+            let color_view = create_postprocess_target(
+                &init.device,
+                pass_width,
+                pass_height,
+                postprocess_format,
+                "Post-process Pass Color Target",
+            );
+
+            postprocess_passes.push(PostProcessPass {
+                pipeline,
+                bind_group_layout,
+                sampler,
+                scale: *scale,
+                color_view,
+                bind_group,
+                uniform_buffer,
+            });
+
+            prev_width = pass_width;
+            prev_height = pass_height;
+        }
+
+        // Final blit: copies whichever texture the chain above ends on
+        // (the last pass's output, or `scene_color_view` when the preset is
+        // empty) onto the view `record_frame` was actually asked to draw
+        // into, since the swapchain/capture texture itself isn't sampleable.
+        let blit_fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Post-process Blit Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("blit_frag.wgsl").into()),
+        });
+        let blit_bind_group_layout =
+            init.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Post-process Blit Bind Group Layout"),
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                    ],
+                });
+        let blit_sampler = init.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let blit_bind_group = {
+            let final_source_view: &wgpu::TextureView = match postprocess_passes.last() {
+                Some(pass) => &pass.color_view,
+                None => &scene_color_view,
+            };
+            init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Post-process Blit Bind Group"),
+                layout: &blit_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(final_source_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&blit_sampler),
+                    },
+                ],
+            })
+        };
+        let blit_pipeline_layout =
+            init.device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Post-process Blit Pipeline Layout"),
+                    bind_group_layouts: &[&blit_bind_group_layout],
+                    push_constant_ranges: &[],
+                });
+        let mut blit_ppl = ws::IRenderPipeline {
+            vs_shader: Some(&fullscreen_vs_shader),
+            fs_shader: Some(&blit_fs_shader),
+            pipeline_layout: Some(&blit_pipeline_layout),
+            vertex_buffer_layout: &[],
+            depth_format: None,
+            ..Default::default()
+        };
+        let blit_pipeline = blit_ppl.new(&init);
+
+        // HUD: a final screen-space pass drawing textured glyph quads built
+        // by `build_hud_vertices`, so FPS/resolution/colormap/isolevel/
+        // strength are visible without watching stdout.
+        let hud_vs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("HUD Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("hud_vert.wgsl").into()),
+        });
+        let hud_fs_shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("HUD Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("hud_frag.wgsl").into()),
+        });
+
+        let hud_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("HUD Uniform Buffer"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        init.queue.write_buffer(
+            &hud_uniform_buffer,
+            0,
+            cast_slice(&[init.config.width as f32, init.config.height as f32, 0.0, 0.0]),
+        );
+
+        let (hud_uniform_bind_group_layout, hud_uniform_bind_group) = ws::create_bind_group(
+            &init.device,
+            vec![wgpu::ShaderStages::VERTEX],
+            &[hud_uniform_buffer.as_entire_binding()],
+        );
+
+        let hud_atlas_image = image::open(HUD_FONT_ATLAS_PATH)
+            .expect("failed to load HUD font atlas")
+            .to_rgba8();
+        let (hud_atlas_width, hud_atlas_height) = hud_atlas_image.dimensions();
+        let hud_atlas_size = wgpu::Extent3d {
+            width: hud_atlas_width,
+            height: hud_atlas_height,
+            depth_or_array_layers: 1,
+        };
+        let hud_atlas_texture = init.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("HUD Font Atlas Texture"),
+            size: hud_atlas_size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        init.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &hud_atlas_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &hud_atlas_image,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * hud_atlas_width),
+                rows_per_image: Some(hud_atlas_height),
+            },
+            hud_atlas_size,
+        );
+        let hud_atlas_view = hud_atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let hud_sampler = init.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let hud_texture_bind_group_layout =
+            init.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("HUD Texture Bind Group Layout"),
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                    ],
+                });
+        let hud_texture_bind_group = init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("HUD Texture Bind Group"),
+            layout: &hud_texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&hud_atlas_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&hud_sampler),
+                },
+            ],
+        });
+
+        let hud_pipeline_layout =
+            init.device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("HUD Pipeline Layout"),
+                    bind_group_layouts: &[
+                        &hud_uniform_bind_group_layout,
+                        &hud_texture_bind_group_layout,
+                    ],
+                    push_constant_ranges: &[],
+                });
+        let hud_vertex_buffer_layout = [VertexBufferLayout {
+            array_stride: std::mem::size_of::<HudVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2],
+        }];
+        let mut hud_ppl = ws::IRenderPipeline {
+            vs_shader: Some(&hud_vs_shader),
+            fs_shader: Some(&hud_fs_shader),
+            pipeline_layout: Some(&hud_pipeline_layout),
+            vertex_buffer_layout: &hud_vertex_buffer_layout,
+            depth_format: None, // always drawn on top, regardless of scene depth
+            ..Default::default()
+        };
+        let hud_pipeline = hud_ppl.new(&init);
+
+        let hud_vertex_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("HUD Vertex Buffer"),
+            size: (HUD_MAX_CHARS as u64) * 6 * std::mem::size_of::<HudVertex>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         Self {
             init,
             pipeline,
             uniform_bind_groups: vec![vert_bind_group, frag_bind_group],
             uniform_buffers: vec![
                 vert_uniform_buffer,
-                light_uniform_buffer,
+                eye_uniform_buffer,
                 material_uniform_buffer,
             ],
 
@@ -490,6 +1491,13 @@ impl State {
             ],
             cs_bind_groups: vec![cs_value_bind_group, cs_bind_group],
 
+            cs_value_bind_group_layout,
+            cs_bind_group_layout,
+            frag_bind_group_layout,
+            cs_table_buffer,
+            cs_colormap_buffer,
+            cdata,
+
             view_mat,
             project_mat,
             msaa_texture_view,
@@ -501,8 +1509,10 @@ impl State {
 
             colormap_direction: 1,
             colormap_reverse: 0,
+            colormap_name: colormap_name.to_string(),
             isolevel: 20.0,
             scale: 0.5,
+            view_mode,
 
             metaball_positions,
             metaball_array,
@@ -510,9 +1520,40 @@ impl State {
             strength_target: 1.0,
             subtract: 1.0,
             subtract_target: 1.0,
+            freeze_targets: false,
             start: std::time::Instant::now(),
             t0: std::time::Instant::now(),
+            headless_sim_time: 0.0,
             fps_counter: ws::FpsCounter::default(),
+            fps: 0.0,
+            last_update_instant: std::time::Instant::now(),
+
+            sphere_pipeline,
+            sphere_vertex_buffer,
+            sphere_index_buffer,
+            sphere_index_count,
+            sphere_instance_buffer,
+            show_metaball_spheres: false,
+
+            light_storage_buffer,
+            light_colors,
+            light_array,
+
+            render_graph: RenderGraph::new(),
+
+            scene_color_view,
+            postprocess_passes,
+            blit_pipeline,
+            blit_bind_group_layout,
+            blit_sampler,
+            blit_bind_group,
+
+            hud_pipeline,
+            hud_uniform_bind_group,
+            hud_texture_bind_group,
+            hud_uniform_buffer,
+            hud_vertex_buffer,
+            hud_vertex_count: 0,
         }
     }
 
@@ -538,7 +1579,316 @@ impl State {
             if self.init.sample_count > 1 {
                 self.msaa_texture_view = ws::create_msaa_texture_view(&self.init);
             }
+            self.resize_postprocess_targets();
+        }
+    }
+
+    /// Rebuilds the post-process chain's intermediate color targets (and the
+    /// bind groups that sample them) at the new window size. Pipelines and
+    /// samplers don't depend on the surface size, so only the targets and
+    /// the bind groups chaining them together need to change here.
+    fn resize_postprocess_targets(&mut self) {
+        let format = self.init.config.format;
+        self.scene_color_view = create_postprocess_target(
+            &self.init.device,
+            self.init.config.width,
+            self.init.config.height,
+            format,
+            "Scene Color Target",
+        );
+
+        let mut prev_width = self.init.config.width;
+        let mut prev_height = self.init.config.height;
+        for index in 0..self.postprocess_passes.len() {
+            let scale = self.postprocess_passes[index].scale;
+            let pass_width = ((prev_width as f32) * scale).round().max(1.0) as u32;
+            let pass_height = ((prev_height as f32) * scale).round().max(1.0) as u32;
+            let color_view = create_postprocess_target(
+                &self.init.device,
+                pass_width,
+                pass_height,
+                format,
+                "Post-process Pass Color Target",
+            );
+
+            let bind_group = {
+                let source_view: &wgpu::TextureView = if index == 0 {
+                    &self.scene_color_view
+                } else {
+                    &self.postprocess_passes[index - 1].color_view
+                };
+                self.init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Post-process Pass Bind Group"),
+                    layout: &self.postprocess_passes[index].bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(source_view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(
+                                &self.postprocess_passes[index].sampler,
+                            ),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: self.postprocess_passes[index].uniform_buffer.as_entire_binding(),
+                        },
+                    ],
+                })
+            };
+
+            let pass = &mut self.postprocess_passes[index];
+            pass.color_view = color_view;
+            pass.bind_group = bind_group;
+
+            prev_width = pass_width;
+            prev_height = pass_height;
         }
+
+        let final_source_view: &wgpu::TextureView = match self.postprocess_passes.last() {
+            Some(pass) => &pass.color_view,
+            None => &self.scene_color_view,
+        };
+        self.blit_bind_group = self.init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Post-process Blit Bind Group"),
+            layout: &self.blit_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(final_source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.blit_sampler),
+                },
+            ],
+        });
+    }
+
+    /// Rebuilds every buffer whose size depends on `resolution` or
+    /// `metaballs_count` and the two compute bind groups that reference
+    /// them, so +/-/[/] in `input` can be used as a live performance/quality
+    /// knob instead of requiring an edit-and-recompile. Mirrors the buffer
+    /// sizing and bind group layout of `State::new` exactly; only the
+    /// buffers that actually change size are recreated, everything else
+    /// (pipelines, the marching-cubes table, the colormap) is reused as-is.
+    fn reallocate(&mut self, new_resolution: u32, new_metaballs_count: u32) {
+        let resol = ws::round_to_multiple(new_resolution, 4);
+        let marching_cube_cells = (resol - 1) * (resol - 1) * (resol - 1);
+        let vertex_count = 3 * 12 * marching_cube_cells;
+        let vertex_buffer_size = 4 * vertex_count;
+        let index_count = 15 * marching_cube_cells;
+        let index_buffer_size = 4 * index_count;
+        let volume_elements = resol * resol * resol;
+
+        let cs_value_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Index Buffer"),
+            size: 4 * volume_elements as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let single_ball_buffer_size: u32 = 3 * 4 + // position: vec3<f32>
+            1 * 4 + // radius f32
+            1 * 4 + // strength: f32
+            1 * 4 + // subtract: f32
+            2 * 4 + // padding
+            0;
+        let balls_buffer_size = single_ball_buffer_size * new_metaballs_count;
+        let metaball_array = vec![0f32; (balls_buffer_size / 4) as usize];
+        let cs_value_metaball_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Metaball Buffer"),
+            size: balls_buffer_size as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let cs_position_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Compute Position Buffer"),
+            size: vertex_buffer_size as u64,
+            usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let cs_normal_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Compute Normal Buffer"),
+            size: vertex_buffer_size as u64,
+            usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let cs_color_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Compute Color Buffer"),
+            size: vertex_buffer_size as u64,
+            usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let cs_index_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Compute Index Buffer"),
+            size: index_buffer_size as u64,
+            usage: wgpu::BufferUsages::INDEX
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let cs_value_bind_group = self.init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Compute Value Bind Group"),
+            layout: &self.cs_value_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: cs_value_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.cs_uniform_buffers[0].as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: cs_value_metaball_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let cs_bind_group = self.init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Compute Bind Group"),
+            layout: &self.cs_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.cs_table_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: cs_value_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: cs_position_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: cs_normal_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: cs_color_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: cs_index_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: self.cs_uniform_buffers[4].as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 7,
+                    resource: self.cs_colormap_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 8,
+                    resource: self.cs_uniform_buffers[2].as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 9,
+                    resource: self.cs_uniform_buffers[3].as_entire_binding(),
+                },
+            ],
+        });
+
+        self.cs_vertex_buffers = vec![
+            cs_value_buffer,
+            cs_position_buffer,
+            cs_normal_buffer,
+            cs_color_buffer,
+        ];
+        self.cs_index_buffer = cs_index_buffer;
+        self.cs_uniform_buffers[1] = cs_value_metaball_buffer;
+        self.cs_bind_groups = vec![cs_value_bind_group, cs_bind_group];
+
+        self.resolution = resol;
+        self.index_count = index_count;
+
+        // grow/shrink the ball set with the same RNG seeding logic `new`
+        // uses, instead of reseeding everything (that would make the sim
+        // jump when only the count changed).
+        let mut rng = rand::thread_rng();
+        let range = Uniform::new(0.0, 1.0);
+        if new_metaballs_count as usize > self.metaball_positions.len() {
+            for _ in self.metaball_positions.len()..new_metaballs_count as usize {
+                self.metaball_positions.push(MetaballPosition {
+                    x: -4.0 * (2.0 * rng.sample(range) - 1.0),
+                    y: -4.0 * (2.0 * rng.sample(range) - 1.0),
+                    z: -4.0 * (2.0 * rng.sample(range) - 1.0),
+                    vx: 1000.0 * rng.sample(range),
+                    vy: 10.0 * (2.0 * rng.sample(range) - 1.0),
+                    vz: 1000.0 * rng.sample(range),
+                    speed: 2.0 * rng.sample(range) + 0.3,
+                });
+            }
+        } else {
+            self.metaball_positions.truncate(new_metaballs_count as usize);
+        }
+        self.metaball_array = metaball_array;
+        self.metaballs_count = new_metaballs_count;
+
+        // point lights and their debug spheres are keyed 1:1 with
+        // metaball_positions, so they're resized the same way.
+        self.light_colors = (0..new_metaballs_count as usize)
+            .map(|i| {
+                let c = &self.cdata[i * self.cdata.len() / new_metaballs_count as usize];
+                [c[0], c[1], c[2]]
+            })
+            .collect();
+        self.light_array = vec![0f32; 8 * new_metaballs_count as usize];
+        self.light_storage_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Point Light Storage Buffer"),
+            size: 32 * new_metaballs_count as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.uniform_bind_groups[1] =
+            self.init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Fragment Uniform Bind Group"),
+                layout: &self.frag_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: self.uniform_buffers[1].as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: self.uniform_buffers[2].as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: self.light_storage_buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+        self.sphere_instance_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Metaball Debug Sphere Instance Buffer"),
+            size: 16 * new_metaballs_count as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        println!(
+            "resolution = {}, metaballs_count = {}",
+            self.resolution, self.metaballs_count
+        );
     }
 
     #[allow(unused_variables)]
@@ -561,13 +1911,143 @@ impl State {
                     self.colormap_reverse = if self.colormap_reverse == 0 { 1 } else { 0 };
                     true
                 }
+                VirtualKeyCode::M => {
+                    self.show_metaball_spheres = !self.show_metaball_spheres;
+                    true
+                }
+                VirtualKeyCode::Equals => {
+                    let new_resolution = (self.resolution + 4).min(256);
+                    self.reallocate(new_resolution, self.metaballs_count);
+                    true
+                }
+                VirtualKeyCode::Minus => {
+                    let new_resolution = (self.resolution.saturating_sub(4)).max(32);
+                    self.reallocate(new_resolution, self.metaballs_count);
+                    true
+                }
+                VirtualKeyCode::RBracket => {
+                    let new_metaballs_count = (self.metaballs_count + 10).min(1000);
+                    self.reallocate(self.resolution, new_metaballs_count);
+                    true
+                }
+                VirtualKeyCode::LBracket => {
+                    let new_metaballs_count = (self.metaballs_count.saturating_sub(10)).max(10);
+                    self.reallocate(self.resolution, new_metaballs_count);
+                    true
+                }
+                VirtualKeyCode::V => {
+                    self.view_mode = (self.view_mode + 1) % VIEW_MODE_COUNT;
+                    self.init.queue.write_buffer(
+                        &self.uniform_buffers[2],
+                        16,
+                        cast_slice(&[self.view_mode, NEAR.to_bits(), FAR.to_bits(), 0u32]),
+                    );
+                    true
+                }
+                VirtualKeyCode::Up => {
+                    self.isolevel += 1.0;
+                    true
+                }
+                VirtualKeyCode::Down => {
+                    self.isolevel = (self.isolevel - 1.0).max(0.0);
+                    true
+                }
+                VirtualKeyCode::Key1 => {
+                    self.colormap_direction = 0;
+                    true
+                }
+                VirtualKeyCode::Key2 => {
+                    self.colormap_direction = 1;
+                    true
+                }
+                VirtualKeyCode::Key3 => {
+                    self.colormap_direction = 2;
+                    true
+                }
+                VirtualKeyCode::Key4 => {
+                    self.colormap_direction = 3;
+                    true
+                }
+                VirtualKeyCode::F => {
+                    self.freeze_targets = !self.freeze_targets;
+                    true
+                }
                 _ => false,
             },
+            WindowEvent::MouseWheel { delta, .. } => {
+                let step = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.05,
+                };
+                self.isolevel = (self.isolevel + step).max(0.0);
+                true
+            }
             _ => false,
         }
     }
 
     fn update(&mut self, _dt: std::time::Duration) {
+        let time = std::time::Instant::now();
+        let dt1 = (time - self.start).as_secs_f32();
+        self.start = time;
+        let elapsed_secs = self.t0.elapsed().as_secs_f32();
+
+        self.step_simulation(dt1, elapsed_secs);
+
+        self.update_hud((self.init.config.width, self.init.config.height));
+    }
+
+    /// Smooths the FPS from the wall-clock delta since the last call (there's
+    /// no reading `ws::FpsCounter`'s internal average, only its stdout dump),
+    /// rebuilds the HUD vertex buffer for the current frame's text, and
+    /// refreshes the screen-size uniform so glyph quads stay pixel-accurate
+    /// across a resize. Window-only: the headless `--headless` capture path
+    /// runs through `update_fixed_step` instead and never needs a HUD.
+    fn update_hud(&mut self, size: (u32, u32)) {
+        let now = std::time::Instant::now();
+        let dt = now.duration_since(self.last_update_instant).as_secs_f32();
+        self.last_update_instant = now;
+        if dt > 0.0 {
+            self.fps = self.fps * 0.9 + (1.0 / dt) * 0.1;
+        }
+
+        let text = format!(
+            "FPS: {:.1}\n{}x{}\ncolormap: {}\nisolevel: {:.2}\nstrength: {:.2}\nsubtract: {:.2}",
+            self.fps,
+            size.0,
+            size.1,
+            self.colormap_name,
+            self.isolevel,
+            self.strength_target,
+            self.subtract_target
+        );
+        let vertices = build_hud_vertices(&text, (8.0, 8.0));
+        self.hud_vertex_count = vertices.len() as u32;
+        self.init
+            .queue
+            .write_buffer(&self.hud_vertex_buffer, 0, cast_slice(&vertices));
+
+        let screen_size = [size.0 as f32, size.1 as f32, 0.0, 0.0];
+        self.init
+            .queue
+            .write_buffer(&self.hud_uniform_buffer, 0, cast_slice(&screen_size));
+    }
+
+    /// Headless counterpart to [`State::update`]. Advances the same
+    /// simulation by a caller-supplied timestep instead of reading
+    /// `self.start`/`self.t0` off the wall clock, so a `--headless`
+    /// capture run produces the same frames on every machine regardless
+    /// of how long rendering/encoding each frame actually took.
+    fn update_fixed_step(&mut self, dt1: f32) {
+        self.headless_sim_time += dt1;
+        self.step_simulation(dt1, self.headless_sim_time);
+    }
+
+    /// Body shared by [`State::update`] and [`State::update_fixed_step`]:
+    /// advances the metaball/light simulation by `dt1` seconds and applies
+    /// the every-5-seconds target reshuffle once `elapsed_secs` crosses the
+    /// threshold.
+    fn step_simulation(&mut self, dt1: f32, elapsed_secs: f32) {
         // update compute buffers for value
         let value_int_params = [self.resolution, self.metaballs_count, 0, 0];
         self.init.queue.write_buffer(
@@ -576,10 +2056,6 @@ impl State {
             bytemuck::cast_slice(&value_int_params),
         );
 
-        let time = std::time::Instant::now();
-        let dt1 = (time - self.start).as_secs_f32();
-        self.start = time;
-
         self.subtract += (self.subtract_target - self.subtract) * dt1 * 0.2;
         self.strength += (self.strength_target - self.strength) * dt1 * 0.2;
 
@@ -637,6 +2113,41 @@ impl State {
             bytemuck::cast_slice(&self.metaball_array),
         );
 
+        let sphere_instances: Vec<[f32; 4]> = self
+            .metaball_positions
+            .iter()
+            .map(|mbp| [mbp.x, mbp.y, mbp.z, (self.strength / self.subtract).sqrt()])
+            .collect();
+        self.init.queue.write_buffer(
+            &self.sphere_instance_buffer,
+            0,
+            bytemuck::cast_slice(&sphere_instances),
+        );
+
+        // point lights ride along with the balls they're attached to;
+        // intensity tracks strength so lights flare up with the same
+        // parameter driving the isosurface's blobbiness.
+        let light_intensity = self.strength;
+        let light_falloff = 2.0f32;
+        for i in 0..self.metaballs_count as usize {
+            let mbp = &self.metaball_positions[i];
+            let color = self.light_colors[i];
+            let offset = i * 8;
+            self.light_array[offset] = mbp.x;
+            self.light_array[offset + 1] = mbp.y;
+            self.light_array[offset + 2] = mbp.z;
+            self.light_array[offset + 3] = light_intensity;
+            self.light_array[offset + 4] = color[0];
+            self.light_array[offset + 5] = color[1];
+            self.light_array[offset + 6] = color[2];
+            self.light_array[offset + 7] = light_falloff;
+        }
+        self.init.queue.write_buffer(
+            &self.light_storage_buffer,
+            0,
+            bytemuck::cast_slice(&self.light_array),
+        );
+
         // update compute buffers
         let int_params = [
             self.resolution,
@@ -663,11 +2174,34 @@ impl State {
             bytemuck::cast_slice(&indirect_array),
         );
 
+        // post-process chain: every pass shares the same resolution/time/
+        // isolevel/scale/colormap snapshot so a preset shader can react to
+        // the same parameters steering the isosurface itself.
+        let postprocess_params = [
+            self.init.config.width as f32,
+            self.init.config.height as f32,
+            elapsed_secs,
+            self.isolevel,
+            self.scale,
+            self.colormap_direction as f32,
+            self.colormap_reverse as f32,
+            0.0,
+        ];
+        for pass in &self.postprocess_passes {
+            self.init.queue.write_buffer(
+                &pass.uniform_buffer,
+                0,
+                bytemuck::cast_slice(&postprocess_params),
+            );
+        }
+
         // update strength and subtract parameters in every 5 secs
-        let elapsed = self.t0.elapsed();
         let mut rng = rand::thread_rng();
         let range = Uniform::new(0.0, 1.0);
-        if elapsed >= std::time::Duration::from_secs(5) {
+        let reshuffle_period = 5.0;
+        let crossed_period =
+            (elapsed_secs / reshuffle_period).floor() > ((elapsed_secs - dt1) / reshuffle_period).floor();
+        if crossed_period && !self.freeze_targets {
             self.subtract_target = 3.0 * rng.sample(range) + 3.0;
             self.strength_target = 3.0 * rng.sample(range) + 3.0;
         }
@@ -679,72 +2213,131 @@ impl State {
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
+        let buffers = self.record_frame(&view);
+
+        self.fps_counter.print_fps(5);
+        self.init.queue.submit(buffers);
+
+        output.present();
+
+        Ok(())
+    }
+
+    /// Records one frame of the compute + main passes into `view` by
+    /// running `self.render_graph` and returns its command buffers in
+    /// submission order. Shared by the windowed `render` path and the
+    /// offscreen `render_to_file` path used by `--headless` so both stay in
+    /// sync.
+    fn record_frame(&mut self, view: &wgpu::TextureView) -> Vec<wgpu::CommandBuffer> {
+        let ctx = GraphContext {
+            state: &*self,
+            view,
+        };
+        self.render_graph.execute(&ctx)
+    }
+
+    /// Draws the HUD glyph quads built by `update_hud` in their own
+    /// pipeline, inside [`MainRenderNode`]'s existing render-pass scope so
+    /// the overlay lands on top of the scene without a separate pass.
+    fn render_hud<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.hud_vertex_count == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.hud_pipeline);
+        render_pass.set_bind_group(0, &self.hud_uniform_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.hud_texture_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.hud_vertex_buffer.slice(..));
+        render_pass.draw(0..self.hud_vertex_count, 0..1);
+    }
+
+    /// Renders one frame into an offscreen texture instead of the window
+    /// surface, reads it back into a padding-stripped pixel buffer (`wgpu`
+    /// requires `bytes_per_row` aligned to `COPY_BYTES_PER_ROW_ALIGNMENT`,
+    /// which rarely matches an arbitrary window width), and writes it to
+    /// `path` with the `image` crate. Used by `--headless` to produce
+    /// turntable animations without ever opening a window.
+    fn render_to_file(&mut self, path: &std::path::Path) {
+        let size = wgpu::Extent3d {
+            width: self.init.config.width,
+            height: self.init.config.height,
+            depth_or_array_layers: 1,
+        };
+        let offscreen_texture = self.init.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Headless Offscreen Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.init.config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = offscreen_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let graph_buffers = self.record_frame(&view);
+
         let mut encoder =
             self.init
                 .device
                 .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                    label: Some("Render Encoder"),
+                    label: Some("Headless Capture Encoder"),
                 });
 
-        // compute pass for value
-        {
-            let mut cs_index_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("Compute value Pass"),
-            });
-            cs_index_pass.set_pipeline(&self.cs_pipelines[0]);
-            cs_index_pass.set_bind_group(0, &self.cs_bind_groups[0], &[]);
-            cs_index_pass.dispatch_workgroups(
-                self.resolution / 4,
-                self.resolution / 4,
-                self.resolution / 4,
-            );
-        }
-
-        // compute pass for vertices
-        {
-            let mut cs_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("Compute Pass"),
-            });
-            cs_pass.set_pipeline(&self.cs_pipelines[1]);
-            cs_pass.set_bind_group(0, &self.cs_bind_groups[1], &[]);
-            cs_pass.dispatch_workgroups(
-                self.resolution / 4,
-                self.resolution / 4,
-                self.resolution / 4,
-            );
-        }
-
-        // render pass
-        {
-            let color_attach = ws::create_color_attachment(&view);
-            let msaa_attach = ws::create_msaa_color_attachment(&view, &self.msaa_texture_view);
-            let color_attachment = if self.init.sample_count == 1 {
-                color_attach
-            } else {
-                msaa_attach
-            };
-            let depth_attachment = ws::create_depth_stencil_attachment(&self.depth_texture_view);
+        let unpadded_bytes_per_row = 4 * self.init.config.width;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = ((unpadded_bytes_per_row + align - 1) / align) * align;
+        let output_buffer = self.init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Headless Readback Buffer"),
+            size: (padded_bytes_per_row * self.init.config.height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &offscreen_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &output_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(self.init.config.height),
+                },
+            },
+            size,
+        );
+        self.init.queue.submit(
+            graph_buffers
+                .into_iter()
+                .chain(iter::once(encoder.finish())),
+        );
 
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(color_attachment)],
-                depth_stencil_attachment: Some(depth_attachment),
-            });
+        output_buffer.slice(..).map_async(wgpu::MapMode::Read, |_| {});
+        self.init.device.poll(wgpu::Maintain::Wait);
 
-            render_pass.set_pipeline(&self.pipeline);
-            render_pass.set_vertex_buffer(0, self.cs_vertex_buffers[1].slice(..));
-            render_pass.set_vertex_buffer(1, self.cs_vertex_buffers[2].slice(..));
-            render_pass.set_vertex_buffer(2, self.cs_vertex_buffers[3].slice(..));
-            render_pass.set_index_buffer(self.cs_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            render_pass.set_bind_group(0, &self.uniform_bind_groups[0], &[]);
-            render_pass.set_bind_group(1, &self.uniform_bind_groups[1], &[]);
-            render_pass.draw_indexed(0..self.index_count, 0, 0..1);
+        let padded = output_buffer.slice(..).get_mapped_range();
+        let mut pixels =
+            Vec::with_capacity((unpadded_bytes_per_row * self.init.config.height) as usize);
+        for row in padded.chunks(padded_bytes_per_row as usize) {
+            pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
         }
-        self.fps_counter.print_fps(5);
-        self.init.queue.submit(iter::once(encoder.finish()));
-        output.present();
+        drop(padded);
+        output_buffer.unmap();
 
-        Ok(())
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("failed to create headless output dir");
+        }
+        image::save_buffer(
+            path,
+            &pixels,
+            self.init.config.width,
+            self.init.config.height,
+            image::ColorType::Rgba8,
+        )
+        .expect("failed to write headless frame");
     }
 }
 
@@ -752,6 +2345,11 @@ fn main() {
     let mut sample_count = 1u32;
     let mut resolution = 192u32;
     let mut colormap_name = "jet";
+    // `--headless <frame_count> <outdir>`: render `frame_count` frames to
+    // `outdir/frame_NNNN.png` via `State::render_to_file` instead of opening
+    // a window, stepping the simulation with a fixed timestep so the
+    // exported sequence is reproducible across machines.
+    let mut headless: Option<(u32, std::path::PathBuf)> = None;
 
     let args: Vec<String> = std::env::args().collect();
     if args.len() > 1 {
@@ -763,6 +2361,10 @@ fn main() {
     if args.len() > 3 {
         colormap_name = &args[3];
     }
+    if args.len() > 4 && args[4] == "--headless" {
+        let frame_count = args[5].parse::<u32>().unwrap();
+        headless = Some((frame_count, std::path::PathBuf::from(&args[6])));
+    }
 
     env_logger::init();
     let event_loop = EventLoop::new();
@@ -773,6 +2375,20 @@ fn main() {
 
     let mut state =
         pollster::block_on(State::new(&window, sample_count, resolution, colormap_name));
+
+    if let Some((frame_count, outdir)) = headless {
+        // Headless: drive the render loop directly instead of waiting on
+        // window events, since nothing will ever present to the window.
+        const HEADLESS_DT: f32 = 1.0 / 60.0;
+        for frame in 0..frame_count {
+            state.update_fixed_step(HEADLESS_DT);
+            let path = outdir.join(format!("frame_{:04}.png", frame));
+            state.render_to_file(&path);
+        }
+        println!("wrote {} headless frame(s) to {:?}", frame_count, outdir);
+        return;
+    }
+
     let render_start_time = std::time::Instant::now();
 
     event_loop.run(move |event, _, control_flow| match event {
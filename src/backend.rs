@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use winit::window::Window;
+
+use crate::reflect::Reflection;
+
+/// Abstracts device/queue/surface creation, shader compilation, and frame
+/// submission behind one interface so application code isn't tied to a
+/// single GPU API. `State` is generic over this trait with `WgpuBackend` as
+/// its default, but only `State<WgpuBackend>` has an impl and `main` always
+/// builds that one; `VulkanoBackend` implements the trait but is not
+/// selectable at startup through config or a feature flag.
+///
+/// This mirrors how other GPU crates isolate all direct API calls behind a
+/// shim module and swap implementations underneath a stable interface,
+/// rather than letting call sites reach directly into `wgpu` or `vulkano`.
+pub trait GraphicsBackend: Sized {
+    type Device;
+    type Queue;
+    type Surface;
+    type ShaderModule;
+
+    /// Creates the device/queue/surface triple for `window`.
+    fn new(window: &Window) -> Self;
+
+    fn device(&self) -> &Self::Device;
+    fn queue(&self) -> &Self::Queue;
+    fn surface(&self) -> &Self::Surface;
+
+    /// Compiles WGSL `source` into this backend's native shader module type,
+    /// running it through `naga` validation first so a type/binding error
+    /// surfaces as a `Reflection`-bearing `Result` rather than a driver panic.
+    fn compile_shader(&self, source: &str) -> Result<(Self::ShaderModule, Reflection), anyhow::Error>;
+
+    /// Submits recorded work for this frame and presents it, if the backend
+    /// has a swapchain to present to.
+    fn submit_and_present(&self);
+}
+
+/// `wgpu`-backed implementation, wrapping the `Device`/`Queue`/`Surface`
+/// triple `State::new` already builds.
+pub struct WgpuBackend {
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    pub surface: wgpu::Surface,
+    pub adapter_info: wgpu::AdapterInfo,
+}
+
+impl GraphicsBackend for WgpuBackend {
+    type Device = wgpu::Device;
+    type Queue = wgpu::Queue;
+    type Surface = wgpu::Surface;
+    type ShaderModule = wgpu::ShaderModule;
+
+    fn new(window: &Window) -> Self {
+        WgpuBackend::with_config(window, &crate::config::EngineConfig::default())
+            .expect("no compatible wgpu adapter/device found at startup")
+    }
+
+    fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    fn surface(&self) -> &wgpu::Surface {
+        &self.surface
+    }
+
+    fn compile_shader(&self, source: &str) -> Result<(wgpu::ShaderModule, Reflection), anyhow::Error> {
+        let (naga_module, _info, reflection) = crate::reflect::reflect_wgsl(source)?;
+        let module = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Naga(std::borrow::Cow::Owned(naga_module)),
+        });
+        Ok((module, reflection))
+    }
+
+    fn submit_and_present(&self) {
+        // Frame submission happens in `State::render`, which needs the
+        // command encoder it built; this hook exists for backends (like a
+        // headless target) that submit without a render-loop-owned encoder.
+    }
+}
+
+impl WgpuBackend {
+    /// Requests the adapter/device using the power preference and fallback
+    /// flag carried by an `EngineConfig`, so `State::new` no longer
+    /// hard-codes them. Features and limits are not taken from the config:
+    /// the device is always requested with `Features::empty()` and
+    /// `Limits::default()`.
+    ///
+    /// Fallible rather than panicking so a config hot-reload (`apply_config`)
+    /// can keep running on the old backend when the requested adapter/device
+    /// combination isn't available, instead of taking the whole app down
+    /// over a reload.
+    pub fn with_config(window: &Window, config: &crate::config::EngineConfig) -> Result<Self, anyhow::Error> {
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let surface = unsafe { instance.create_surface(window) };
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: config.power_preference,
+            compatible_surface: Some(&surface),
+            force_fallback_adapter: config.force_fallback_adapter,
+        }))
+        .ok_or_else(|| anyhow::anyhow!("no compatible wgpu adapter found"))?;
+        let adapter_info = adapter.get_info();
+
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                features: wgpu::Features::empty(),
+                limits: wgpu::Limits::default(),
+            },
+            None,
+        ))?;
+
+        Ok(WgpuBackend { device, queue, surface, adapter_info })
+    }
+}
+
+/// `vulkano`-backed implementation. `ShaderStore` already owns the
+/// long-lived `Device` this wraps; the backend trait just gives callers a
+/// uniform way to reach it alongside `WgpuBackend`.
+pub struct VulkanoBackend {
+    pub device: Arc<vulkano::device::Device>,
+    pub queue: Arc<vulkano::device::Queue>,
+    pub shaders: crate::shader::ShaderStore,
+}
+
+impl GraphicsBackend for VulkanoBackend {
+    type Device = Arc<vulkano::device::Device>;
+    type Queue = Arc<vulkano::device::Queue>;
+    type Surface = ();
+    type ShaderModule = crate::shader::ShaderHandle;
+
+    fn new(_window: &Window) -> Self {
+        let instance = vulkano::instance::Instance::new(vulkano::instance::InstanceCreateInfo::default())
+            .expect("failed to create vulkano instance");
+        let physical = vulkano::device::physical::PhysicalDevice::enumerate(&instance)
+            .next()
+            .expect("no vulkano-compatible physical device found");
+        let queue_family = physical
+            .queue_families()
+            .find(|q| q.supports_graphics())
+            .expect("no graphics-capable queue family");
+
+        let (device, mut queues) = vulkano::device::Device::new(
+            physical,
+            vulkano::device::DeviceCreateInfo {
+                queue_create_infos: vec![vulkano::device::QueueCreateInfo::family(queue_family)],
+                ..Default::default()
+            },
+        )
+        .expect("failed to create vulkano device");
+        let queue = queues.next().expect("device did not return a queue");
+
+        let shaders = crate::shader::ShaderStore::new(device.clone());
+
+        VulkanoBackend { device, queue, shaders }
+    }
+
+    fn device(&self) -> &Arc<vulkano::device::Device> {
+        &self.device
+    }
+
+    fn queue(&self) -> &Arc<vulkano::device::Queue> {
+        &self.queue
+    }
+
+    fn surface(&self) -> &() {
+        &()
+    }
+
+    fn compile_shader(&self, source: &str) -> Result<(crate::shader::ShaderHandle, Reflection), anyhow::Error> {
+        let (_naga_module, _info, reflection) = crate::reflect::reflect_wgsl(source)?;
+        let handle = self.shaders.load("<inline>", source)?;
+        Ok((handle, reflection))
+    }
+
+    fn submit_and_present(&self) {
+        // No swapchain yet on this backend; frames are consumed by whatever
+        // offscreen/export path is driving it.
+    }
+}
@@ -0,0 +1,158 @@
+use cgmath::{perspective, Deg, InnerSpace, Matrix, Matrix4, Point3, SquareMatrix, Vector3, Vector4};
+
+/// cgmath's projection matrices target OpenGL's clip-space z range (-1..1);
+/// wgpu expects 0..1. Every cgmath-based wgpu camera needs this correction
+/// baked into the matrix it uploads.
+#[rustfmt::skip]
+pub const OPENGL_TO_WGPU_MATRIX: Matrix4<f32> = Matrix4::new(
+    1.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, 0.5, 0.0,
+    0.0, 0.0, 0.5, 1.0,
+);
+
+/// Orbit camera: `eye` is derived from `yaw`/`pitch`/`distance` around
+/// `target` rather than stored directly, so `State::input` only has to nudge
+/// three scalars and `build_view_projection_matrix` stays the single place
+/// that turns them into a matrix.
+pub struct Camera {
+    pub target: Point3<f32>,
+    pub up: Vector3<f32>,
+    pub aspect: f32,
+    pub fovy: Deg<f32>,
+    pub znear: f32,
+    pub zfar: f32,
+    pub distance: f32,
+    pub yaw: Deg<f32>,
+    pub pitch: Deg<f32>,
+}
+
+impl Camera {
+    pub fn new(aspect: f32) -> Self {
+        Camera {
+            target: Point3::new(0.0, 0.0, 0.0),
+            up: Vector3::unit_y(),
+            aspect,
+            fovy: Deg(45.0),
+            znear: 0.1,
+            zfar: 100.0,
+            distance: 5.0,
+            yaw: Deg(45.0),
+            pitch: Deg(30.0),
+        }
+    }
+
+    pub fn eye(&self) -> Point3<f32> {
+        let yaw = cgmath::Rad::from(self.yaw);
+        let pitch = cgmath::Rad::from(self.pitch);
+        let offset = Vector3::new(
+            pitch.0.cos() * yaw.0.cos(),
+            pitch.0.sin(),
+            pitch.0.cos() * yaw.0.sin(),
+        )
+        .normalize()
+            * self.distance;
+        self.target + offset
+    }
+
+    pub fn build_view_projection_matrix(&self) -> Matrix4<f32> {
+        let view = Matrix4::look_at_rh(self.eye(), self.target, self.up);
+        let proj = perspective(self.fovy, self.aspect, self.znear, self.zfar);
+        OPENGL_TO_WGPU_MATRIX * proj * view
+    }
+
+    /// Called from `State::input` on mouse-drag motion; `delta_pitch` is
+    /// clamped away from the poles so the camera can't flip past straight up
+    /// or straight down.
+    pub fn orbit(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        self.yaw += Deg(delta_yaw);
+        self.pitch = Deg((self.pitch.0 + delta_pitch).clamp(-89.0, 89.0));
+    }
+
+    pub fn zoom(&mut self, delta: f32) {
+        self.distance = (self.distance + delta).clamp(1.0, 50.0);
+    }
+}
+
+/// The GPU-side mirror of [`Camera`]'s view-projection matrix; `State` keeps
+/// one of these alongside its `Camera` and re-runs `update` whenever the
+/// camera moves or `resize_state` changes the aspect ratio.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CameraUniform {
+    view_proj: [[f32; 4]; 4],
+}
+
+impl CameraUniform {
+    pub fn new() -> Self {
+        CameraUniform {
+            view_proj: Matrix4::identity().into(),
+        }
+    }
+
+    pub fn update(&mut self, camera: &Camera) {
+        self.view_proj = camera.build_view_projection_matrix().into();
+    }
+}
+
+impl Default for CameraUniform {
+    fn default() -> Self {
+        CameraUniform::new()
+    }
+}
+
+/// The 6 view-frustum planes, extracted from a view-projection matrix by
+/// the Gribb/Hartmann method so terrain chunks can be culled against the
+/// camera without re-deriving FOV/aspect/near/far by hand. Each plane is
+/// `(a, b, c, d)` with `a*x + b*y + c*z + d >= 0` inside the frustum.
+///
+/// wgpu's clip space has `z` in `0..1` (not OpenGL's `-1..1`), so the
+/// near/far planes here are `row2` / `row3 - row2` rather than the
+/// `row3 + row2` / `row3 - row2` pair the classic derivation uses.
+pub struct Frustum {
+    planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    pub fn from_view_projection(view_proj: Matrix4<f32>) -> Self {
+        let row0 = view_proj.row(0);
+        let row1 = view_proj.row(1);
+        let row2 = view_proj.row(2);
+        let row3 = view_proj.row(3);
+
+        let mut planes = [
+            row3 + row0, // left
+            row3 - row0, // right
+            row3 + row1, // bottom
+            row3 - row1, // top
+            row2,        // near
+            row3 - row2, // far
+        ];
+        for plane in &mut planes {
+            let normal_len = (plane.x * plane.x + plane.y * plane.y + plane.z * plane.z).sqrt();
+            if normal_len > f32::EPSILON {
+                *plane /= normal_len;
+            }
+        }
+        Frustum { planes }
+    }
+
+    /// Whether an axis-aligned box could be at least partially visible:
+    /// for every plane, tests only the box corner furthest along the
+    /// plane's normal (the "positive vertex" trick), so a box fully on the
+    /// outside of any single plane is culled without checking all 8
+    /// corners against all 6 planes.
+    pub fn intersects_aabb(&self, min: [f32; 3], max: [f32; 3]) -> bool {
+        for plane in &self.planes {
+            let p = [
+                if plane.x >= 0.0 { max[0] } else { min[0] },
+                if plane.y >= 0.0 { max[1] } else { min[1] },
+                if plane.z >= 0.0 { max[2] } else { min[2] },
+            ];
+            if plane.x * p[0] + plane.y * p[1] + plane.z * p[2] + plane.w < 0.0 {
+                return false;
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use notify::{watcher, RecursiveMode, Watcher};
+
+/// Engine-level settings that previously were hard-coded in `State::new`
+/// (`PowerPreference::default()`, `force_fallback_adapter: false`), plus the
+/// present mode and shader paths. Parsed from a small S-expression file so
+/// artists/developers can flip to a low-power adapter live, without a
+/// recompile. GPU features and limits are not part of the config;
+/// `WgpuBackend::with_config` always uses `Features::empty()` and
+/// `Limits::default()`.
+///
+/// Example file:
+/// ```text
+/// (power-preference low-power)
+/// (force-fallback-adapter false)
+/// (present-mode fifo)
+/// (shader-paths "src/shader.wgsl")
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineConfig {
+    pub power_preference: wgpu::PowerPreference,
+    pub force_fallback_adapter: bool,
+    pub present_mode: wgpu::PresentMode,
+    pub shader_paths: Vec<PathBuf>,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        EngineConfig {
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: false,
+            present_mode: wgpu::PresentMode::Fifo,
+            shader_paths: Vec::new(),
+        }
+    }
+}
+
+/// What changed between an old and new `EngineConfig`, so `State::apply_config`
+/// only rebuilds the pieces that actually moved instead of tearing everything
+/// down on every reload.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConfigDiff {
+    pub adapter_changed: bool,
+    pub surface_changed: bool,
+}
+
+impl EngineConfig {
+    pub fn diff(&self, other: &EngineConfig) -> ConfigDiff {
+        ConfigDiff {
+            adapter_changed: self.power_preference != other.power_preference
+                || self.force_fallback_adapter != other.force_fallback_adapter,
+            surface_changed: self.present_mode != other.present_mode,
+        }
+    }
+
+    /// Parses the tiny S-expression dialect used by the config file: a
+    /// sequence of `(key value...)` forms, one per line, comments starting
+    /// with `;`.
+    pub fn parse(source: &str) -> Result<EngineConfig, anyhow::Error> {
+        let mut config = EngineConfig::default();
+
+        for line in source.lines() {
+            let line = line.split(';').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let line = line
+                .strip_prefix('(')
+                .and_then(|l| l.strip_suffix(')'))
+                .ok_or_else(|| anyhow::anyhow!("malformed config form: {line:?}"))?;
+
+            let mut parts = line.split_whitespace();
+            let key = parts
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("empty config form"))?;
+            let rest: Vec<&str> = parts.collect();
+
+            match key {
+                "power-preference" => {
+                    config.power_preference = match rest.first().copied() {
+                        Some("low-power") => wgpu::PowerPreference::LowPower,
+                        Some("high-performance") => wgpu::PowerPreference::HighPerformance,
+                        _ => wgpu::PowerPreference::default(),
+                    };
+                }
+                "force-fallback-adapter" => {
+                    config.force_fallback_adapter = rest.first().copied() == Some("true");
+                }
+                "present-mode" => {
+                    config.present_mode = match rest.first().copied() {
+                        Some("immediate") => wgpu::PresentMode::Immediate,
+                        Some("mailbox") => wgpu::PresentMode::Mailbox,
+                        _ => wgpu::PresentMode::Fifo,
+                    };
+                }
+                "shader-paths" => {
+                    config.shader_paths = rest
+                        .iter()
+                        .map(|s| PathBuf::from(s.trim_matches('"')))
+                        .collect();
+                }
+                other => return Err(anyhow::anyhow!("unknown config key: {other}")),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Starts a background thread that watches `path` and sends a freshly parsed
+/// `EngineConfig` down the returned channel whenever it changes, coalescing
+/// bursts of saves the same way `ShaderStore::spawn_watch` does.
+pub fn spawn_watch(path: impl AsRef<Path>, debounce: Duration) -> Receiver<Result<EngineConfig, anyhow::Error>> {
+    let path = path.as_ref().to_path_buf();
+    let (raw_tx, raw_rx) = channel();
+    let mut fs_watcher = watcher(raw_tx, debounce).expect("failed to start config file watcher");
+    if let Err(err) = fs_watcher.watch(&path, RecursiveMode::NonRecursive) {
+        eprintln!("config watch: failed to watch {}: {}", path.display(), err);
+    }
+
+    let (tx, rx) = channel();
+    std::thread::spawn(move || {
+        let _fs_watcher = fs_watcher;
+        for event in raw_rx {
+            let changed = matches!(
+                event,
+                notify::DebouncedEvent::Write(_)
+                    | notify::DebouncedEvent::Create(_)
+                    | notify::DebouncedEvent::Chmod(_)
+            );
+            if !changed {
+                continue;
+            }
+            let result = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|source| EngineConfig::parse(&source));
+            let _ = tx.send(result);
+        }
+    });
+
+    rx
+}
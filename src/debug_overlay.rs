@@ -0,0 +1,147 @@
+use std::time::Instant;
+
+/// One entry in the overlay's shader list: a human-readable name, and the
+/// last compile error reported for it (if any), so a bad edit shows up
+/// in-app instead of only on stdout.
+pub struct ShaderStatus {
+    pub name: String,
+    pub last_error: Option<String>,
+}
+
+/// Live state the overlay reports on: frame timing, the adapter/limits
+/// chosen in `State::new`, and the shaders currently tracked by the hot
+/// reload subsystem.
+pub struct DebugInfo {
+    pub adapter_name: String,
+    pub limits: wgpu::Limits,
+    pub shaders: Vec<ShaderStatus>,
+}
+
+/// Which shader (if any) the user asked to reload this frame by clicking its
+/// "reload" button in the overlay.
+pub type ReloadRequest = Option<String>;
+
+/// Thin wrapper around an `egui` context + winit/wgpu integration, so
+/// `State::update`/`render` can feed it window events and composite its pass
+/// on top of the scene without scattering egui plumbing through the render
+/// loop.
+pub struct DebugOverlay {
+    ctx: egui::Context,
+    winit_state: egui_winit::State,
+    renderer: egui_wgpu::Renderer,
+    last_frame: Instant,
+    frame_time_ms: f32,
+}
+
+impl DebugOverlay {
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat, event_loop: &winit::event_loop::EventLoop<()>) -> Self {
+        DebugOverlay {
+            ctx: egui::Context::default(),
+            winit_state: egui_winit::State::new(event_loop),
+            renderer: egui_wgpu::Renderer::new(device, output_format, None, 1),
+            last_frame: Instant::now(),
+            frame_time_ms: 0.0,
+        }
+    }
+
+    /// Replaces the `egui_wgpu::Renderer`, which owns GPU resources tied to
+    /// the `wgpu::Device` it was created against. Called instead of `new`
+    /// when `State::apply_config` swaps adapters: `ctx`/`winit_state` aren't
+    /// device-bound and don't need `event_loop` to rebuild, only `renderer`
+    /// does.
+    pub fn rebuild_renderer(&mut self, device: &wgpu::Device, output_format: wgpu::TextureFormat) {
+        self.renderer = egui_wgpu::Renderer::new(device, output_format, None, 1);
+    }
+
+    /// Feeds a window event into egui; returns `true` if egui consumed it
+    /// (e.g. a click landed on the overlay) so the caller's own `input`
+    /// doesn't also act on it.
+    pub fn on_window_event(&mut self, window: &winit::window::Window, event: &winit::event::WindowEvent) -> bool {
+        self.winit_state.on_event(&self.ctx, event).consumed
+    }
+
+    pub fn update(&mut self) {
+        let now = Instant::now();
+        self.frame_time_ms = (now - self.last_frame).as_secs_f32() * 1000.0;
+        self.last_frame = now;
+    }
+
+    /// Runs the overlay's UI for this frame and records its draw calls into
+    /// `encoder`/`view`, composited on top of whatever the scene pass
+    /// already wrote with `LoadOp::Load`. Returns the name of a shader the
+    /// user clicked "reload" for, if any.
+    pub fn render(
+        &mut self,
+        window: &winit::window::Window,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        screen_size: (u32, u32),
+        info: &DebugInfo,
+    ) -> ReloadRequest {
+        let raw_input = self.winit_state.take_egui_input(window);
+        let mut reload_request = None;
+
+        let output = self.ctx.run(raw_input, |ctx| {
+            egui::Window::new("Debug").show(ctx, |ui| {
+                ui.label(format!("frame time: {:.2} ms", self.frame_time_ms));
+                ui.label(format!("fps: {:.0}", 1000.0 / self.frame_time_ms.max(0.001)));
+                ui.separator();
+                ui.label(format!("adapter: {}", info.adapter_name));
+                ui.label(format!("max bind groups: {}", info.limits.max_bind_groups));
+                ui.label(format!("max buffer size: {}", info.limits.max_buffer_size));
+                ui.separator();
+                ui.label("shaders:");
+                for shader in &info.shaders {
+                    ui.horizontal(|ui| {
+                        ui.label(&shader.name);
+                        if ui.button("reload").clicked() {
+                            reload_request = Some(shader.name.clone());
+                        }
+                    });
+                    if let Some(err) = &shader.last_error {
+                        ui.colored_label(egui::Color32::RED, err);
+                    }
+                }
+            });
+        });
+
+        self.winit_state
+            .handle_platform_output(window, &self.ctx, output.platform_output);
+
+        let clipped_primitives = self.ctx.tessellate(output.shapes);
+        let screen_descriptor = egui_wgpu::renderer::ScreenDescriptor {
+            size_in_pixels: [screen_size.0, screen_size.1],
+            pixels_per_point: window.scale_factor() as f32,
+        };
+
+        for (id, delta) in &output.textures_delta.set {
+            self.renderer.update_texture(device, queue, *id, delta);
+        }
+        self.renderer
+            .update_buffers(device, queue, encoder, &clipped_primitives, &screen_descriptor);
+
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("egui debug overlay"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+            self.renderer.render(&mut pass, &clipped_primitives, &screen_descriptor);
+        }
+
+        for id in &output.textures_delta.free {
+            self.renderer.free_texture(id);
+        }
+
+        reload_request
+    }
+}
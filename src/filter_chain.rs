@@ -0,0 +1,221 @@
+/// Path to the post-process preset: one fragment shader path per line,
+/// run in order over the terrain's rendered color texture. `#` starts a
+/// comment; a missing file means an empty chain rather than a hard error,
+/// so the filter list stays opt-in.
+const FILTER_PRESET_PATH: &str = "filters.preset";
+
+/// Parses [`FILTER_PRESET_PATH`]. Returns an empty `Vec` when the file
+/// can't be read.
+fn load_filter_preset(path: &str) -> Vec<String> {
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    source
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn create_ping_pong_target(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    label: &str,
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
+
+/// One fullscreen fragment-shader stage in a [`FilterChain`]. Every stage
+/// shares the chain's fullscreen-triangle vertex shader, bind group layout,
+/// and sampler - only the fragment shader (and so the pipeline) differs
+/// between passes.
+struct FilterPass {
+    pipeline: wgpu::RenderPipeline,
+}
+
+/// RetroArch/slang-style post-process chain: an ordered list of fullscreen
+/// fragment shaders read from [`FILTER_PRESET_PATH`], each sampling the
+/// previous stage's output and writing the next. Passes ping-pong between
+/// two offscreen color targets instead of allocating one target per pass,
+/// since only the immediately-preceding stage's output is ever read.
+///
+/// An empty chain (no preset file, or one with no entries) makes
+/// [`FilterChain::execute`] a no-op that hands its input straight back, so
+/// `RenderGraph` doesn't need a separate "chain disabled" code path.
+pub struct FilterChain {
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    passes: Vec<FilterPass>,
+    ping_pong: Option<[(wgpu::Texture, wgpu::TextureView); 2]>,
+    ping_pong_size: (u32, u32),
+}
+
+impl FilterChain {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let vs_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Filter Chain Fullscreen Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!(
+                "fullscreen.wgsl"
+            ))),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Filter Pass Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Filter Pass Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let passes = load_filter_preset(FILTER_PRESET_PATH)
+            .into_iter()
+            .enumerate()
+            .map(|(index, shader_path)| {
+                let fs_source = std::fs::read_to_string(&shader_path).unwrap_or_else(|err| {
+                    panic!("failed to read filter pass shader {shader_path:?}: {err}")
+                });
+                let fs_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some(&format!("Filter Pass {index} Fragment Shader")),
+                    source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Owned(fs_source)),
+                });
+                let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(&format!("Filter Pass {index} Pipeline")),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState { module: &vs_shader, entry_point: "vs_main", buffers: &[] },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &fs_shader,
+                        entry_point: "fs_main",
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format,
+                            blend: None,
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                });
+                FilterPass { pipeline }
+            })
+            .collect();
+
+        FilterChain {
+            bind_group_layout,
+            sampler,
+            passes,
+            ping_pong: None,
+            ping_pong_size: (0, 0),
+        }
+    }
+
+    /// Allocates the two ping-pong targets on first use and reallocates
+    /// only when `width`/`height` actually changed, mirroring
+    /// `RenderGraph::ensure_scene_color`.
+    fn ensure_ping_pong(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat, width: u32, height: u32) {
+        if self.ping_pong.is_some() && self.ping_pong_size == (width, height) {
+            return;
+        }
+        self.ping_pong = Some([
+            create_ping_pong_target(device, format, width, height, "Filter Chain Ping Target"),
+            create_ping_pong_target(device, format, width, height, "Filter Chain Pong Target"),
+        ]);
+        self.ping_pong_size = (width, height);
+    }
+
+    /// Runs every preset pass over `input`, ping-ponging between the two
+    /// offscreen targets, and returns the final stage's output. With no
+    /// passes configured, returns `input` unchanged and allocates nothing.
+    pub fn execute(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        input_texture: &wgpu::Texture,
+        input_view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        if self.passes.is_empty() {
+            return (input_texture.clone(), input_view.clone());
+        }
+        self.ensure_ping_pong(device, format, width, height);
+        let targets = self.ping_pong.as_ref().unwrap();
+
+        let mut src_view = input_view.clone();
+        let mut last_written = 0;
+        for (index, pass) in self.passes.iter().enumerate() {
+            let dst_index = index % 2;
+            let dst_view = &targets[dst_index].1;
+
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Filter Pass Bind Group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&src_view) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                ],
+            });
+
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Filter Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: dst_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: true },
+                })],
+                depth_stencil_attachment: None,
+            });
+            render_pass.set_pipeline(&pass.pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+            drop(render_pass);
+
+            src_view = dst_view.clone();
+            last_written = dst_index;
+        }
+
+        let (final_texture, final_view) = &targets[last_written];
+        (final_texture.clone(), final_view.clone())
+    }
+}
@@ -1,3 +1,11 @@
+mod backend;
+mod camera;
+mod config;
+mod debug_overlay;
+mod filter_chain;
+mod reflect;
+mod render_graph;
+mod shader;
 mod terrain;
 
 use std::iter;
@@ -5,182 +13,538 @@ use winit::{event_loop::{EventLoop, ControlFlow}, window::WindowBuilder};
 use wgpu::util::DeviceExt;
 use bytemuck::cast_slice;
 
-struct Vertex {
-    position: [f32; 3],
+use backend::{GraphicsBackend, WgpuBackend};
+use camera::{Camera, CameraUniform, Frustum};
+use config::EngineConfig;
+use debug_overlay::{DebugInfo, DebugOverlay, ShaderStatus};
+use render_graph::{ChunkDraw, GraphContext, RenderGraph};
+use terrain::Vertex;
+
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Builds a `DEPTH_FORMAT` render-attachment texture sized to `config`,
+/// shared by `State::new` and `resize_state` (and `apply_config` when the
+/// adapter changes) so there's one place the depth buffer's size/format
+/// invariant lives.
+fn create_depth_texture(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Depth Texture"),
+        size: wgpu::Extent3d {
+            width: config.width.max(1),
+            height: config.height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
 }
 
-struct State {
-    surface: wgpu::Surface,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
-    render_pipeline: wgpu::RenderPipeline,
+/// One [`terrain::Chunk`]'s vertex/index buffers, uploaded once in
+/// `State::new` and kept alongside its AABB so `State::render` can hand
+/// [`ChunkDraw`] views of them to the render graph every frame without
+/// re-deriving bounds from the vertex data.
+struct GpuChunk {
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
-    indices: Vec<u32>, // Make sure to include this field
+    index_count: u32,
+    min: [f32; 3],
+    max: [f32; 3],
 }
 
-impl State {
-    async fn new(window: &winit::window::Window) -> Self {
-        let size = window.inner_size();
+/// Generates the terrain and uploads each chunk's vertex/index buffers onto
+/// `device`. Pulled out of `State::new` so `apply_config` can call it again
+/// against a freshly created device when the adapter changes - chunk buffers
+/// created on the old device would otherwise outlive it and panic the next
+/// `render()` with a device mismatch.
+fn build_chunks(device: &wgpu::Device) -> Vec<GpuChunk> {
+    let terrain = terrain::Terrain::new(256);
+    terrain
+        .chunks
+        .iter()
+        .map(|chunk| {
+            let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Terrain Chunk Vertex Buffer"),
+                contents: cast_slice(chunk.vertices.as_slice()),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            });
+            let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Terrain Chunk Index Buffer"),
+                contents: cast_slice(chunk.indices.as_slice()),
+                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            });
+            GpuChunk {
+                vertex_buffer,
+                index_buffer,
+                index_count: chunk.indices.len() as u32,
+                min: chunk.min,
+                max: chunk.max,
+            }
+        })
+        .collect()
+}
+
+/// Builds the camera uniform buffer/bind group and the terrain render
+/// pipeline against `device`/`format`. Pulled out of `State::new` for the
+/// same reason as [`build_chunks`]: `apply_config` needs to rebuild these
+/// against a new device on an adapter swap rather than leaving `State`
+/// holding a pipeline and camera buffer the old device owned.
+fn build_pipeline_resources(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    camera_uniform: &CameraUniform,
+    shader: &wgpu::ShaderModule,
+) -> (wgpu::Buffer, wgpu::BindGroup, wgpu::RenderPipeline) {
+    let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Camera Buffer"),
+        contents: cast_slice(&[*camera_uniform]),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
 
-        // Set up surface and adapter
-        let instance = wgpu::Instance::new(wgpu::Backends::all());
-        let surface = unsafe { instance.create_surface(&window) };
-        let adapter = instance.request_adapter(
-            &wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
+    let camera_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Camera Bind Group Layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
             },
-        ).await.unwrap();
-
-        // Set up device and queue
-        let (device, queue) = adapter.request_device(
-            &wgpu::DeviceDescriptor {
-                label: None,
-                features: wgpu::Features::empty(),
-                limits: wgpu::Limits::default(),
-            }
-        ).await.unwrap();
-
-        // Create render pipeline with shaders
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: None,
-            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!("shader.wgsl"))),
-        });
-
-        // Create vertex and index buffers
-        let terrain = terrain::Terrain::new(256);
-
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: None,
-            contents: cast_slice(terrain.vertices.as_slice()),
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-        });
-
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: None,
-            contents: cast_slice(terrain.indices.as_slice()),
-            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST, // Corrected to INDEX
-        });
-
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: None,
-            bind_group_layouts: &[],
-            push_constant_ranges: &[],
-        });
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: None,
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: std::mem::size_of::<Vertex>() as u32,
-                    step_mode: wgpu::VertexStepMode::Vertex,
-                    attributes: &[wgpu::VertexAttribute {
+            count: None,
+        }],
+    });
+
+    let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Camera Bind Group"),
+        layout: &camera_bind_group_layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: camera_buffer.as_entire_binding(),
+        }],
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[&camera_bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<Vertex>() as u64,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &[
+                    wgpu::VertexAttribute {
                         offset: 0,
                         shader_location: 0,
                         format: wgpu::VertexFormat::Float32x3
-                    }],
-                }],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[wgpu::ColorTargetState {
-                    format: surface.get_supported_formats(&device)[0],
-                    blend: None,
-                    write_mask: wgpu::ColorWrites::ALL
-                }],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-                ..Default::default()
-            },
-            depth_stencil: None, // Add this if you have depth testing/stenciling
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-        });
+                    },
+                    wgpu::VertexAttribute {
+                        offset: std::mem::size_of::<[f32; 3]>() as u64,
+                        shader_location: 1,
+                        format: wgpu::VertexFormat::Float32x3
+                    },
+                ],
+            }],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: "fs_main",
+            targets: &[wgpu::ColorTargetState {
+                format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL
+            }],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: Some(wgpu::Face::Back),
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+            ..Default::default()
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    });
+
+    (camera_buffer, camera_bind_group, render_pipeline)
+}
+
+/// Generic over `GraphicsBackend` so the same terrain pipeline/draw code can
+/// run against either `WgpuBackend` or a future `VulkanoBackend`; today's
+/// pipeline/surface wiring below is written against `wgpu` types directly
+/// and only makes sense for `B = WgpuBackend`, but the field access already
+/// goes through `self.backend` so swapping `B` only means rewriting this
+/// impl block, not every call site.
+struct State<B: GraphicsBackend = WgpuBackend> {
+    backend: B,
+    engine_config: EngineConfig,
+    surface_config: wgpu::SurfaceConfiguration,
+    render_pipeline: wgpu::RenderPipeline,
+    chunks: Vec<GpuChunk>,
+    depth_texture: wgpu::Texture,
+    depth_texture_view: wgpu::TextureView,
+    camera: Camera,
+    camera_uniform: CameraUniform,
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+    mouse_pressed: bool,
+    last_cursor_pos: Option<(f64, f64)>,
+    render_graph: RenderGraph,
+    debug_overlay: DebugOverlay,
+    shader_path: std::path::PathBuf,
+    shader_last_error: Option<String>,
+}
+
+/// Default terrain shader source: the config's `shader-paths` entry (if one
+/// names this shader) is read from disk so edits take effect on reload,
+/// falling back to the copy baked in at compile time when that file can't be
+/// read (e.g. running from an install without the source tree alongside it).
+fn load_shader_source(path: &std::path::Path) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|_| include_str!("shader.wgsl").to_string())
+}
+
+impl State<WgpuBackend> {
+    async fn new(window: &winit::window::Window, event_loop: &EventLoop<()>, engine_config: EngineConfig) -> Self {
+        let size = window.inner_size();
+        let backend = WgpuBackend::with_config(window, &engine_config)
+            .expect("no compatible wgpu adapter/device found at startup");
+        let device = &backend.device;
+        let surface = &backend.surface;
+
+        let format = surface.get_supported_formats(device)[0];
+        // `PresentNode` (render_graph.rs) finishes the frame with
+        // `copy_texture_to_texture` into the acquired swapchain texture, so
+        // the surface needs `COPY_DST` alongside `RENDER_ATTACHMENT` or that
+        // copy fails wgpu validation every frame. `COPY_DST` is part of
+        // `wgpu::Surface::SUPPORTED_USAGES` on every backend wgpu targets
+        // here (Vulkan/Metal/DX12/GL), so no capability probe is needed.
+        let surface_config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST,
+            format,
+            width: size.width,
+            height: size.height,
+            present_mode: engine_config.present_mode,
+        };
+        surface.configure(device, &surface_config);
+
+        // Generate the terrain as independent chunks and upload each one's
+        // vertex/index buffers separately, so `render` can frustum-cull and
+        // `draw_indexed` per chunk instead of the whole terrain at once.
+        let chunks = build_chunks(device);
+
+        let camera = Camera::new(size.width as f32 / size.height as f32);
+        let mut camera_uniform = CameraUniform::new();
+        camera_uniform.update(&camera);
+
+        let shader_path = engine_config
+            .shader_paths
+            .first()
+            .cloned()
+            .unwrap_or_else(|| std::path::PathBuf::from("src/shader.wgsl"));
+        let (shader_module, _reflection) = backend
+            .compile_shader(&load_shader_source(&shader_path))
+            .expect("initial terrain shader failed to compile");
+
+        let (camera_buffer, camera_bind_group, render_pipeline) =
+            build_pipeline_resources(device, format, &camera_uniform, &shader_module);
+
+        let debug_overlay = DebugOverlay::new(&backend.device, format, event_loop);
+
+        let (depth_texture, depth_texture_view) = create_depth_texture(device, &surface_config);
 
         State {
-            surface,
-            device,
-            queue,
+            backend,
+            engine_config,
+            surface_config,
             render_pipeline,
-            vertex_buffer,
-            index_buffer,
-            indices: terrain.indices, // Include the indices here
+            chunks,
+            depth_texture,
+            depth_texture_view,
+            camera,
+            camera_uniform,
+            camera_buffer,
+            camera_bind_group,
+            mouse_pressed: false,
+            last_cursor_pos: None,
+            render_graph: RenderGraph::new(device, surface_config.format),
+            debug_overlay,
+            shader_path,
+            shader_last_error: None,
         }
     }
 
     fn resize_state(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
-        let size = self.surface.configure(&self.device, &wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface.get_supported_formats(&device)[0],
-            width: new_size.width,
-            height: new_size.height,
-            present_mode: wgpu::PresentMode::Fifo,
-        });
+        if new_size.width == 0 || new_size.height == 0 {
+            return;
+        }
+        self.surface_config.width = new_size.width;
+        self.surface_config.height = new_size.height;
+        self.backend.surface.configure(&self.backend.device, &self.surface_config);
+        let (depth_texture, depth_texture_view) =
+            create_depth_texture(&self.backend.device, &self.surface_config);
+        self.depth_texture = depth_texture;
+        self.depth_texture_view = depth_texture_view;
+        self.camera.aspect = new_size.width as f32 / new_size.height as f32;
     }
 
-    fn input(&mut self, _event: &winit::event::Event<'_>) -> bool {
-        false
+    /// Diffs `new_config` against the config `State` is currently running
+    /// with and only rebuilds what actually changed: a new adapter/device if
+    /// the power preference or fallback flag moved, or just a surface
+    /// reconfigure if only the present mode did. On an adapter change every
+    /// GPU resource tied to the old `wgpu::Device` - the depth texture, the
+    /// render pipeline, the camera buffer/bind group, each chunk's
+    /// vertex/index buffers, the render graph's transient targets, and the
+    /// debug overlay's `egui_wgpu::Renderer` - is rebuilt against the new
+    /// one, since mixing resources from two devices panics on the next
+    /// `render()`.
+    fn apply_config(&mut self, window: &winit::window::Window, new_config: EngineConfig) {
+        let diff = self.engine_config.diff(&new_config);
+
+        // `with_config` can fail to find a matching adapter/device (e.g. a
+        // `power_preference` flip with no second GPU, or a fallback-adapter
+        // toggle on a machine without one). Keep rendering on the old
+        // backend rather than taking the whole app down over a reload.
+        let mut adapter_changed = diff.adapter_changed;
+        if diff.adapter_changed {
+            match WgpuBackend::with_config(window, &new_config) {
+                Ok(backend) => {
+                    self.backend = backend;
+                    // `with_config` creates a brand-new `wgpu::Surface` too, and a
+                    // different adapter (or backend, under `wgpu::Backends::all()`)
+                    // is not guaranteed to prefer the old surface's format.
+                    self.surface_config.format = self
+                        .backend
+                        .surface
+                        .get_supported_formats(&self.backend.device)[0];
+                }
+                Err(err) => {
+                    eprintln!("config reload: keeping previous backend, {err}");
+                    adapter_changed = false;
+                }
+            }
+        }
+
+        self.surface_config.present_mode = new_config.present_mode;
+        if adapter_changed || diff.surface_changed {
+            self.backend
+                .surface
+                .configure(&self.backend.device, &self.surface_config);
+        }
+
+        if adapter_changed {
+            // Recompile against the new device before borrowing `self.backend`
+            // below - the old device's shader module can't be reused across
+            // the swap any more than the chunk buffers or camera bind group can.
+            let (shader_module, _reflection) = self
+                .backend
+                .compile_shader(&load_shader_source(&self.shader_path))
+                .expect("terrain shader failed to compile against the new adapter");
+
+            let device = &self.backend.device;
+            let format = self.surface_config.format;
+
+            let (depth_texture, depth_texture_view) = create_depth_texture(device, &self.surface_config);
+            self.depth_texture = depth_texture;
+            self.depth_texture_view = depth_texture_view;
+
+            self.chunks = build_chunks(device);
+
+            self.camera_uniform.update(&self.camera);
+            let (camera_buffer, camera_bind_group, render_pipeline) =
+                build_pipeline_resources(device, format, &self.camera_uniform, &shader_module);
+            self.camera_buffer = camera_buffer;
+            self.camera_bind_group = camera_bind_group;
+            self.render_pipeline = render_pipeline;
+
+            self.render_graph = RenderGraph::new(device, format);
+            self.debug_overlay.rebuild_renderer(device, format);
+        }
+
+        self.engine_config = new_config;
     }
 
-    fn update(&mut self) {}
+    fn input(&mut self, window: &winit::window::Window, event: &winit::event::WindowEvent) -> bool {
+        if self.debug_overlay.on_window_event(window, event) {
+            return true;
+        }
 
-    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        let output = self.surface.get_current_texture()?;
-        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
-
-        {
-            let _render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.1, g: 0.2, b: 0.3, a: 1.0 }),
-                        store: true // Corrected to be the right type
+        match event {
+            winit::event::WindowEvent::MouseInput {
+                state,
+                button: winit::event::MouseButton::Left,
+                ..
+            } => {
+                self.mouse_pressed = *state == winit::event::ElementState::Pressed;
+                if !self.mouse_pressed {
+                    self.last_cursor_pos = None;
+                }
+                true
+            }
+            winit::event::WindowEvent::CursorMoved { position, .. } => {
+                let pos = (position.x, position.y);
+                if self.mouse_pressed {
+                    if let Some((last_x, last_y)) = self.last_cursor_pos {
+                        let delta_yaw = (pos.0 - last_x) as f32 * 0.2;
+                        let delta_pitch = (last_y - pos.1) as f32 * 0.2;
+                        self.camera.orbit(delta_yaw, delta_pitch);
                     }
-                })],
-                depth_stencil_attachment: None,
-            });
+                }
+                self.last_cursor_pos = Some(pos);
+                false
+            }
+            winit::event::WindowEvent::MouseWheel { delta, .. } => {
+                let step = match delta {
+                    winit::event::MouseScrollDelta::LineDelta(_, y) => *y,
+                    winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.05,
+                };
+                self.camera.zoom(-step);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Rereads `self.shader_path` and recompiles the terrain shader through
+    /// `backend.compile_shader`, rebuilding the pipeline on success. Mirrors
+    /// `ShaderStore::reload`'s failure handling: a bad edit only updates
+    /// `shader_last_error` for the debug overlay to show, leaving the
+    /// previously working pipeline in place instead of tearing it down.
+    fn reload_shader(&mut self) {
+        let source = load_shader_source(&self.shader_path);
+        match self.backend.compile_shader(&source) {
+            Ok((shader_module, _reflection)) => {
+                let (camera_buffer, camera_bind_group, render_pipeline) = build_pipeline_resources(
+                    &self.backend.device,
+                    self.surface_config.format,
+                    &self.camera_uniform,
+                    &shader_module,
+                );
+                self.camera_buffer = camera_buffer;
+                self.camera_bind_group = camera_bind_group;
+                self.render_pipeline = render_pipeline;
+                self.shader_last_error = None;
+            }
+            Err(err) => {
+                self.shader_last_error = Some(err.to_string());
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        self.debug_overlay.update();
+        self.camera_uniform.update(&self.camera);
+        self.backend
+            .queue
+            .write_buffer(&self.camera_buffer, 0, cast_slice(&[self.camera_uniform]));
+    }
+
+    fn render(&mut self, window: &winit::window::Window) -> Result<(), wgpu::SurfaceError> {
+        let output = self.backend.surface.get_current_texture()?;
+        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self.backend.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        let frustum = Frustum::from_view_projection(self.camera.build_view_projection_matrix());
+        let chunk_draws: Vec<ChunkDraw> = self
+            .chunks
+            .iter()
+            .map(|chunk| ChunkDraw {
+                vertex_buffer: &chunk.vertex_buffer,
+                index_buffer: &chunk.index_buffer,
+                index_count: chunk.index_count,
+                min: chunk.min,
+                max: chunk.max,
+            })
+            .collect();
+
+        let graph_ctx = GraphContext {
+            device: &self.backend.device,
+            surface_config: &self.surface_config,
+            output_texture: &output.texture,
+            depth_view: &self.depth_texture_view,
+            render_pipeline: &self.render_pipeline,
+            camera_bind_group: &self.camera_bind_group,
+            frustum: &frustum,
+            chunks: &chunk_draws,
+        };
+        self.render_graph.execute(&graph_ctx, &mut encoder);
 
-            // Execute render pipeline
-            _render_pass.set_pipeline(&self.render_pipeline);
-            _render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            _render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            _render_pass.draw_indexed(0..self.indices.len() as u32, 0, 0..1); // Adjust the draw call to your needs
+        let shader_name = self.shader_path.display().to_string();
+        let debug_info = DebugInfo {
+            adapter_name: self.backend.adapter_info.name.clone(),
+            limits: self.backend.device.limits(),
+            shaders: vec![ShaderStatus {
+                name: shader_name.clone(),
+                last_error: self.shader_last_error.clone(),
+            }],
+        };
+        let reload_request = self.debug_overlay.render(
+            window,
+            &self.backend.device,
+            &self.backend.queue,
+            &mut encoder,
+            &view,
+            (self.surface_config.width, self.surface_config.height),
+            &debug_info,
+        );
+        if let Some(name) = reload_request {
+            if name == shader_name {
+                self.reload_shader();
+            }
         }
 
-        self.queue.submit(iter::once(encoder.finish()));
+        self.backend.queue.submit(iter::once(encoder.finish()));
         output.present();
 
         Ok(())
     }
 }
 
+const CONFIG_PATH: &str = "engine.cfg";
+
 fn main() {
     let event_loop = EventLoop::new();
     let window = WindowBuilder::new().build(&event_loop).unwrap();
-    let mut state = pollster::block_on(State::new(&window));
+
+    let engine_config = std::fs::read_to_string(CONFIG_PATH)
+        .ok()
+        .and_then(|source| EngineConfig::parse(&source).ok())
+        .unwrap_or_default();
+    let config_updates = config::spawn_watch(CONFIG_PATH, std::time::Duration::from_millis(200));
+
+    let mut state = pollster::block_on(State::new(&window, &event_loop, engine_config));
 
     event_loop.run(move |event, _, control_flow| match event {
         winit::event::Event::WindowEvent { ref event, window_id } if window_id == window.id() => {
-            if !state.input(event) {
+            if !state.input(&window, event) {
                 match event {
                     winit::event::WindowEvent::CloseRequested
                     | winit::event::WindowEvent::KeyboardInput {
@@ -200,8 +564,18 @@ fn main() {
             }
         },
         winit::event::Event::RedrawRequested(_) => {
-            state.render().unwrap();
+            for update in config_updates.try_iter() {
+                match update {
+                    Ok(new_config) => state.apply_config(&window, new_config),
+                    Err(err) => eprintln!("engine config reload failed: {err}"),
+                }
+            }
+            state.update();
+            state.render(&window).unwrap();
+        }
+        winit::event::Event::MainEventsCleared => {
+            window.request_redraw();
         }
         _ => {}
     });
-}
\ No newline at end of file
+}
@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+
+use naga::back::spv;
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+/// A single `@group(n) @binding(m)` slot discovered while reflecting a
+/// shader, keyed the way pipeline-layout code wants to consume it.
+#[derive(Debug, Clone)]
+pub struct BindingSlot {
+    pub group: u32,
+    pub binding: u32,
+    pub name: String,
+}
+
+/// Everything `State` needs to rebuild a pipeline layout automatically when
+/// a hot-reloaded shader's interface changes.
+#[derive(Debug, Clone, Default)]
+pub struct Reflection {
+    pub entry_points: Vec<String>,
+    pub bind_group_bindings: Vec<BindingSlot>,
+    /// `(stage, offset, size)` - the stage the range is visible to, and its
+    /// byte offset/size within that stage's push-constant block.
+    pub push_constant_ranges: Vec<(naga::ShaderStage, u32, u32)>,
+    /// Vertex shader input locations, by entry point name.
+    pub vertex_locations: BTreeMap<String, Vec<u32>>,
+}
+
+/// A WGSL parse/type error, returned instead of a bare panic. A parse error
+/// keeps the span `naga` attached to it, so the caller can show it next to
+/// the offending line in an editor or overlay; a validation error carries
+/// only the validator's message, without its span.
+#[derive(Debug, thiserror::Error)]
+pub enum ReflectError {
+    #[error("failed to parse WGSL: {0}")]
+    Parse(#[from] naga::front::wgsl::ParseError),
+    #[error("shader failed validation: {0}")]
+    Validate(String),
+    #[error("failed to translate to SPIR-V: {0}")]
+    Spirv(#[from] naga::back::spv::Error),
+}
+
+/// Parses and validates a WGSL source string, returning the validated
+/// `naga::Module`, the `naga::valid::ModuleInfo` that validation produced,
+/// and a [`Reflection`] describing its interface.
+///
+/// This runs ahead of handing source to a backend's `create_shader_module` /
+/// `ShaderModule::from_source`, so a parse error surfaces as a
+/// `ReflectError::Parse` with its span, and a type or binding error as a
+/// `ReflectError::Validate` message (no span), instead of reaching the GPU
+/// driver at all. The
+/// `ModuleInfo` is handed back rather than discarded so callers that also
+/// need SPIR-V (see [`wgsl_to_spirv`]) don't have to validate the same
+/// module twice.
+pub fn reflect_wgsl(source: &str) -> Result<(naga::Module, naga::valid::ModuleInfo, Reflection), ReflectError> {
+    let module = naga::front::wgsl::parse_str(source)?;
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::all());
+    let info = validator
+        .validate(&module)
+        .map_err(|err| ReflectError::Validate(err.to_string()))?;
+
+    let mut reflection = Reflection::default();
+
+    for (handle, entry_point) in module.entry_points.iter().zip(info.iter()) {
+        let _ = entry_point;
+        reflection.entry_points.push(handle.name.clone());
+    }
+
+    for (_, global) in module.global_variables.iter() {
+        let Some(binding) = &global.binding else { continue };
+        let name = global.name.clone().unwrap_or_default();
+        reflection.bind_group_bindings.push(BindingSlot {
+            group: binding.group,
+            binding: binding.binding,
+            name,
+        });
+    }
+
+    // WGSL has no push-constant syntax of its own; `naga`'s WGSL front end
+    // surfaces them as globals in `AddressSpace::PushConstant`, laid out
+    // starting at offset 0 within whichever stage reads them. A global can
+    // be read from more than one entry point, so tag it with every stage
+    // that actually uses it (per the validated `ModuleInfo`) rather than
+    // guessing - `wgpu::PushConstantRange` needs an exact `ShaderStages`
+    // mask, and getting it wrong either panics on overlap validation or
+    // assigns the wrong bytes to the wrong stage.
+    for (handle, global) in module.global_variables.iter() {
+        if global.space != naga::AddressSpace::PushConstant {
+            continue;
+        }
+        let size = module.types[global.ty].inner.size(module.to_ctx());
+        let mut stages: Vec<naga::ShaderStage> = module
+            .entry_points
+            .iter()
+            .zip(info.iter())
+            .filter(|(_, func_info)| !func_info[handle].is_empty())
+            .map(|(entry_point, _)| entry_point.stage)
+            .collect();
+        stages.dedup();
+        for stage in stages {
+            reflection.push_constant_ranges.push((stage, 0, size));
+        }
+    }
+
+    for entry_point in &module.entry_points {
+        if entry_point.stage != naga::ShaderStage::Vertex {
+            continue;
+        }
+        let locations = entry_point
+            .function
+            .arguments
+            .iter()
+            .filter_map(|arg| match arg.binding {
+                Some(naga::Binding::Location { location, .. }) => Some(location),
+                _ => None,
+            })
+            .collect();
+        reflection
+            .vertex_locations
+            .insert(entry_point.name.clone(), locations);
+    }
+
+    Ok((module, info, reflection))
+}
+
+/// Translates an already-validated WGSL module to SPIR-V bytes, so the same
+/// source can target either the `wgpu` `State` path or the `vulkano` side.
+///
+/// Takes the `ModuleInfo` [`reflect_wgsl`] already produced instead of
+/// re-validating `module`, since `Validator::validate` isn't free and every
+/// caller of this function already has one from reflecting the same module.
+pub fn wgsl_to_spirv(module: &naga::Module, info: &naga::valid::ModuleInfo) -> Result<Vec<u32>, ReflectError> {
+    let words = spv::write_vec(module, info, &spv::Options::default(), None)?;
+    Ok(words)
+}
@@ -0,0 +1,290 @@
+use crate::camera::Frustum;
+use crate::filter_chain::FilterChain;
+
+/// A GPU resource a [`RenderNode`] reads or writes. [`RenderGraph::order`]
+/// derives a pass sequence from these instead of `State::render`
+/// hand-sequencing passes itself.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResourceHandle {
+    SceneColor,
+    FilteredColor,
+    PresentedColor,
+}
+
+/// One terrain chunk's GPU-resident buffers plus its local AABB. `State`
+/// owns the buffers (one pair per [`crate::terrain::Chunk`]) and rebuilds
+/// this borrowed view each frame so [`TerrainNode`] can cull and draw
+/// chunks without owning their storage itself.
+pub struct ChunkDraw<'a> {
+    pub vertex_buffer: &'a wgpu::Buffer,
+    pub index_buffer: &'a wgpu::Buffer,
+    pub index_count: u32,
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+/// Resources a node needs to record its pass, borrowed from `State` rather
+/// than the whole struct so a node only sees what its own pass touches.
+pub struct GraphContext<'a> {
+    pub device: &'a wgpu::Device,
+    pub surface_config: &'a wgpu::SurfaceConfiguration,
+    pub output_texture: &'a wgpu::Texture,
+    pub depth_view: &'a wgpu::TextureView,
+    pub render_pipeline: &'a wgpu::RenderPipeline,
+    pub camera_bind_group: &'a wgpu::BindGroup,
+    pub frustum: &'a Frustum,
+    pub chunks: &'a [ChunkDraw<'a>],
+}
+
+/// One pass in the render graph. Declares the resources it reads and writes
+/// so [`RenderGraph`] can order passes instead of the sequence being
+/// hardcoded into `State::render`. The current color target is handed in
+/// separately from `GraphContext` rather than looked up by `ResourceHandle`,
+/// since today's nodes form a strict chain; a node that doesn't produce a
+/// new target (most of them) returns `None` and `RenderGraph::execute`
+/// keeps passing the same one along. `record` takes `&mut self` so a node
+/// like [`FilterChainNode`] can own GPU state (ping-pong targets) that
+/// outlives a single frame instead of reallocating it every call.
+trait RenderNode {
+    fn reads(&self) -> &'static [ResourceHandle];
+    fn writes(&self) -> &'static [ResourceHandle];
+    fn record(
+        &mut self,
+        ctx: &GraphContext,
+        color_texture: &wgpu::Texture,
+        color_view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Option<(wgpu::Texture, wgpu::TextureView)>;
+}
+
+/// Draws the terrain's chunks into the offscreen `SceneColor` target instead
+/// of the swapchain view directly, so a later node ([`FilterChainNode`])
+/// has an intermediate texture to work from before present.
+/// Each chunk is frustum-culled against `ctx.frustum` before its
+/// `draw_indexed`, so chunks outside the camera's view cost nothing beyond
+/// the AABB test. Writes [`ResourceHandle::SceneColor`].
+struct TerrainNode;
+
+impl RenderNode for TerrainNode {
+    fn reads(&self) -> &'static [ResourceHandle] {
+        &[]
+    }
+
+    fn writes(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::SceneColor]
+    }
+
+    fn record(
+        &mut self,
+        ctx: &GraphContext,
+        _color_texture: &wgpu::Texture,
+        color_view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Option<(wgpu::Texture, wgpu::TextureView)> {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Terrain Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.1, g: 0.2, b: 0.3, a: 1.0 }),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: ctx.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: true,
+                }),
+                stencil_ops: None,
+            }),
+        });
+
+        render_pass.set_pipeline(ctx.render_pipeline);
+        render_pass.set_bind_group(0, ctx.camera_bind_group, &[]);
+        for chunk in ctx.chunks {
+            if !ctx.frustum.intersects_aabb(chunk.min, chunk.max) {
+                continue;
+            }
+            render_pass.set_vertex_buffer(0, chunk.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(chunk.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..chunk.index_count, 0, 0..1);
+        }
+        None
+    }
+}
+
+/// Runs the terrain's rendered `SceneColor` target through the crate's
+/// [`FilterChain`] before the present copy. Reads
+/// [`ResourceHandle::SceneColor`], writes [`ResourceHandle::FilteredColor`].
+/// With no preset passes configured the chain is a pass-through, so this
+/// node always sits in the graph rather than being conditionally inserted.
+struct FilterChainNode {
+    chain: FilterChain,
+}
+
+impl RenderNode for FilterChainNode {
+    fn reads(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::SceneColor]
+    }
+
+    fn writes(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::FilteredColor]
+    }
+
+    fn record(
+        &mut self,
+        ctx: &GraphContext,
+        color_texture: &wgpu::Texture,
+        color_view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Option<(wgpu::Texture, wgpu::TextureView)> {
+        Some(self.chain.execute(
+            ctx.device,
+            ctx.surface_config.format,
+            ctx.surface_config.width.max(1),
+            ctx.surface_config.height.max(1),
+            color_texture,
+            color_view,
+            encoder,
+        ))
+    }
+}
+
+/// Copies the finished `FilteredColor` target onto the swapchain texture.
+/// Reads [`ResourceHandle::FilteredColor`], writes
+/// [`ResourceHandle::PresentedColor`]; the graph's terminal node.
+struct PresentNode;
+
+impl RenderNode for PresentNode {
+    fn reads(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::FilteredColor]
+    }
+
+    fn writes(&self) -> &'static [ResourceHandle] {
+        &[ResourceHandle::PresentedColor]
+    }
+
+    fn record(
+        &mut self,
+        ctx: &GraphContext,
+        color_texture: &wgpu::Texture,
+        _color_view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Option<(wgpu::Texture, wgpu::TextureView)> {
+        encoder.copy_texture_to_texture(
+            wgpu::ImageCopyTexture {
+                texture: color_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyTexture {
+                texture: ctx.output_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d {
+                width: ctx.surface_config.width.max(1),
+                height: ctx.surface_config.height.max(1),
+                depth_or_array_layers: 1,
+            },
+        );
+        None
+    }
+}
+
+/// Small declarative render graph replacing the hardcoded clear-and-draw
+/// that used to live inline in `State::render`. Nodes declare the
+/// [`ResourceHandle`]s they read/write; [`RenderGraph::order`] derives a
+/// dependency order from that instead of the sequence being hand-written.
+/// The three nodes here form a strict chain, but a depth prepass or a
+/// lighting pass can be inserted as an additional node without
+/// `State::render` changing at all.
+pub struct RenderGraph {
+    nodes: Vec<Box<dyn RenderNode>>,
+    scene_color: Option<(wgpu::Texture, wgpu::TextureView, u32, u32)>,
+}
+
+impl RenderGraph {
+    pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
+        RenderGraph {
+            nodes: vec![
+                Box::new(TerrainNode),
+                Box::new(FilterChainNode { chain: FilterChain::new(device, surface_format) }),
+                Box::new(PresentNode),
+            ],
+            scene_color: None,
+        }
+    }
+
+    /// Returns the `SceneColor` transient target sized to `ctx.surface_config`,
+    /// allocating it on first use and reallocating only when the surface
+    /// size actually changed rather than every frame.
+    fn ensure_scene_color(&mut self, ctx: &GraphContext) -> (wgpu::Texture, wgpu::TextureView) {
+        let (width, height) = (ctx.surface_config.width.max(1), ctx.surface_config.height.max(1));
+        let needs_new = match &self.scene_color {
+            Some((_, _, w, h)) => *w != width || *h != height,
+            None => true,
+        };
+        if needs_new {
+            let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Scene Color Texture"),
+                size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: ctx.surface_config.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::COPY_SRC
+                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.scene_color = Some((texture, view, width, height));
+        }
+        let (texture, view, _, _) = self.scene_color.as_ref().unwrap();
+        (texture.clone(), view.clone())
+    }
+
+    /// Topologically sorts `nodes`: node `i` must record after every node
+    /// `j` whose output it reads. Today's nodes form a strict chain, so this
+    /// only matters once a node not strictly dependent on its predecessor
+    /// (e.g. a shadow pass) is added.
+    fn order(&self) -> Vec<usize> {
+        let depends_on = |i: usize, j: usize| {
+            self.nodes[i].reads().iter().any(|h| self.nodes[j].writes().contains(h))
+        };
+
+        let mut placed = vec![false; self.nodes.len()];
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while order.len() < self.nodes.len() {
+            let next = (0..self.nodes.len())
+                .find(|&i| {
+                    !placed[i] && (0..self.nodes.len()).all(|j| j == i || placed[j] || !depends_on(i, j))
+                })
+                .expect("render graph has a dependency cycle");
+            placed[next] = true;
+            order.push(next);
+        }
+        order
+    }
+
+    /// Records every node, in dependency order, into `encoder` - the frame's
+    /// shared encoder, not one per node, since `State::render` already owns
+    /// it and submits it once after this returns. Each node is handed the
+    /// current color target and may return a new one (e.g. the filter
+    /// chain's output); a node that doesn't produce one leaves the current
+    /// target unchanged for whichever node records next.
+    pub fn execute(&mut self, ctx: &GraphContext, encoder: &mut wgpu::CommandEncoder) {
+        let (mut color_texture, mut color_view) = self.ensure_scene_color(ctx);
+        for i in self.order() {
+            if let Some((texture, view)) = self.nodes[i].record(ctx, &color_texture, &color_view, encoder) {
+                color_texture = texture;
+                color_view = view;
+            }
+        }
+    }
+}
@@ -1,67 +1,209 @@
-use std::sync::{Arc, Mutex};
-use vulkano::shader::ShaderModule;
-use vulkano::safe_hardware_access::Instance;
-use notify::{Watcher, RecursiveMode, watcher};
-use std::sync::mpsc::channel;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
-pub struct ShaderLoader {
-    vert_shader_path: String,
-    frag_shader_path: String,
-    shader_module: Arc<Mutex<Option<(ShaderModule, ShaderModule)>>>,
+use notify::{watcher, RecursiveMode, Watcher};
+use slab::Slab;
+use vulkano::device::Device;
+use vulkano::shader::ShaderModule;
+
+use crate::reflect::{self, Reflection};
+
+/// Opaque handle to a shader module stored in a [`ShaderStore`].
+///
+/// Handles stay valid across reloads: `reload` replaces the slab entry in
+/// place instead of issuing a new one, so callers can embed a `ShaderHandle`
+/// in a pipeline descriptor once and keep using it after the source changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShaderHandle(usize);
+
+/// Read guard over a stored shader module.
+///
+/// Holds the store's read lock for its lifetime, so the watcher thread
+/// blocks on `reload` until every outstanding guard is dropped.
+pub struct ShaderRef<'a> {
+    guard: std::sync::RwLockReadGuard<'a, Slab<ShaderModule>>,
+    handle: ShaderHandle,
 }
 
-impl ShaderLoader {
-    pub fn new(vert_shader_path: &str, frag_shader_path: &str) -> Self {
-        let shader_module = Arc::new(Mutex::new(None));
-        let loader = ShaderLoader {
-            vert_shader_path: vert_shader_path.to_string(),
-            frag_shader_path: frag_shader_path.to_string(),
-            shader_module,
-        };
+impl<'a> std::ops::Deref for ShaderRef<'a> {
+    type Target = ShaderModule;
 
-        // Start watching for file changes
-        loader.watch_for_changes();
+    fn deref(&self) -> &ShaderModule {
+        &self.guard[self.handle.0]
+    }
+}
 
-        loader
+/// Tracks the source path a loaded shader was compiled from, so the watcher
+/// can map a file-system event back to the handle that owns it.
+struct Entry {
+    path: PathBuf,
+    reflection: Reflection,
+}
+
+/// Handle-based store for compiled shader modules.
+///
+/// Unlike a one-shot loader, `ShaderStore` owns a long-lived `Device` and
+/// keeps every compiled module behind a single `RwLock<Slab<_>>`: the render
+/// thread can read modules through a [`ShaderHandle`] while a watcher thread
+/// recompiles others in the background. A reload compiles the replacement
+/// module first and only swaps it into the slab on success, so a broken edit
+/// leaves the previously working module in place instead of panicking.
+pub struct ShaderStore {
+    device: Arc<Device>,
+    modules: RwLock<Slab<ShaderModule>>,
+    entries: RwLock<Slab<Entry>>,
+}
+
+impl ShaderStore {
+    pub fn new(device: Arc<Device>) -> Self {
+        ShaderStore {
+            device,
+            modules: RwLock::new(Slab::new()),
+            entries: RwLock::new(Slab::new()),
+        }
     }
 
-    fn watch_for_changes(&self) {
-        let (tx, rx) = channel();
-        let mut watcher = watcher(tx, Duration::from_secs(2)).unwrap();
+    /// Compiles `source` and returns a stable handle for it.
+    ///
+    /// `source` is first parsed and validated through `naga` so a parse
+    /// error (with its span) or a type or binding error (message only) is
+    /// reported instead of reaching the GPU driver as a raw compile failure.
+    pub fn load(&self, path: impl Into<PathBuf>, source: &str) -> Result<ShaderHandle, anyhow::Error> {
+        let (naga_module, info, reflection) = reflect::reflect_wgsl(source)?;
+        let spirv = reflect::wgsl_to_spirv(&naga_module, &info)?;
+        let module = unsafe { ShaderModule::from_words(self.device.clone(), &spirv)? };
+
+        let mut modules = self.modules.write().unwrap();
+        let mut entries = self.entries.write().unwrap();
+        let slot = modules.insert(module);
+        let slot2 = entries.insert(Entry { path: path.into(), reflection });
+        debug_assert_eq!(slot, slot2, "modules and entries slabs must stay in lockstep");
+
+        Ok(ShaderHandle(slot))
+    }
+
+    /// Recompiles `source` and swaps it into `handle`'s slot on success.
+    ///
+    /// If validation or compilation fails, the handle keeps pointing at
+    /// whatever module it held before the call, so a syntax error during
+    /// live editing never leaves the renderer without a usable shader.
+    pub fn reload(&self, handle: ShaderHandle, source: &str) -> Result<(), anyhow::Error> {
+        let (naga_module, info, reflection) = reflect::reflect_wgsl(source)?;
+        let spirv = reflect::wgsl_to_spirv(&naga_module, &info)?;
+        let module = unsafe { ShaderModule::from_words(self.device.clone(), &spirv)? };
+
+        let mut modules = self.modules.write().unwrap();
+        let slot = modules
+            .get_mut(handle.0)
+            .ok_or_else(|| anyhow::anyhow!("unknown shader handle {:?}", handle))?;
+        let _old = std::mem::replace(slot, module);
+
+        self.entries.write().unwrap()[handle.0].reflection = reflection;
+
+        Ok(())
+    }
+
+    /// Reflection data (entry points, bind-group bindings, vertex input
+    /// locations) last extracted for `handle`, used by `State` to rebuild
+    /// pipeline layouts when a shader's interface changes across a reload.
+    pub fn reflection_of(&self, handle: ShaderHandle) -> Reflection {
+        self.entries.read().unwrap()[handle.0].reflection.clone()
+    }
+
+    /// Borrows the currently compiled module for `handle`.
+    pub fn get(&self, handle: ShaderHandle) -> ShaderRef<'_> {
+        let guard = self.modules.read().unwrap();
+        assert!(guard.contains(handle.0), "unknown shader handle {:?}", handle);
+        ShaderRef { guard, handle }
+    }
 
-        watcher.watch(&self.vert_shader_path, RecursiveMode::NonRecursive).unwrap();
-        watcher.watch(&self.frag_shader_path, RecursiveMode::NonRecursive).unwrap();
+    /// Path a handle was originally loaded from, used by the watcher to map
+    /// file events back to handles.
+    pub(crate) fn path_of(&self, handle: ShaderHandle) -> PathBuf {
+        self.entries.read().unwrap()[handle.0].path.clone()
+    }
+
+    pub(crate) fn handles(&self) -> Vec<ShaderHandle> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(slot, _)| ShaderHandle(slot))
+            .collect()
+    }
 
+    /// Starts a background thread that watches every path registered via
+    /// `load` and reloads only the handles whose files actually changed.
+    ///
+    /// Events within `debounce` of each other are coalesced by `notify`'s own
+    /// debounced channel, so a burst of editor saves across several watched
+    /// files triggers one recompile per file rather than one per write.
+    /// Compile failures are sent down the returned channel instead of
+    /// unwinding the watcher thread, so a syntax error during live editing
+    /// never takes the watcher down with it.
+    pub fn spawn_watch(self: &Arc<Self>, debounce: Duration) -> Receiver<WatchEvent> {
+        let (raw_tx, raw_rx) = channel();
+        let mut fs_watcher = watcher(raw_tx, debounce).expect("failed to start shader file watcher");
+
+        let paths: HashMap<PathBuf, ShaderHandle> = self
+            .handles()
+            .into_iter()
+            .map(|handle| (self.path_of(handle), handle))
+            .collect();
+        for path in paths.keys() {
+            if let Err(err) = fs_watcher.watch(path, RecursiveMode::NonRecursive) {
+                eprintln!("shader watch: failed to watch {}: {}", path.display(), err);
+            }
+        }
+
+        let (tx, rx) = channel();
+        let store = self.clone();
         std::thread::spawn(move || {
-            loop {
-                match rx.recv() {
-                    Ok(event) => {
-                        println!("File changed: {:?}", event);
-                        // Reload shaders
-                        let vert_code = std::fs::read_to_string(&self.vert_shader_path).unwrap();
-                        let frag_code = std::fs::read_to_string(&self.frag_shader_path).unwrap();
-
-                        let device = vulkano::instance::Instance::new(None, &vulkano::instance::InstanceExtensions::none())
-                            .get_default_physical_device().expect("Couldn't get physical device")
-                            .open()
-                            .unwrap();
-
-                        let vert_shader_module = ShaderModule::from_source(&device, &vert_code).unwrap();
-                        let frag_shader_module = ShaderModule::from_source(&device, &frag_code).unwrap();
-
-                        {
-                            let mut shader_module = self.shader_module.lock().unwrap();
-                            *shader_module = Some((vert_shader_module, frag_shader_module));
-                        }
+            // Keep the raw `notify::Watcher` alive for the life of the thread.
+            let _fs_watcher = fs_watcher;
+            for event in raw_rx {
+                let changed_path = match event {
+                    notify::DebouncedEvent::Write(p)
+                    | notify::DebouncedEvent::Create(p)
+                    | notify::DebouncedEvent::Chmod(p) => p,
+                    notify::DebouncedEvent::Error(err, path) => {
+                        let _ = tx.send(WatchEvent::WatchError(path, err.to_string()));
+                        continue;
                     }
-                    Err(e) => println!("watch error: {:?}", e),
-                }
+                    _ => continue,
+                };
+
+                let Some(&handle) = paths.get(&changed_path) else {
+                    continue;
+                };
+
+                let result = std::fs::read_to_string(&changed_path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|source| store.reload(handle, &source));
+
+                let event = match result {
+                    Ok(()) => WatchEvent::Reloaded(handle),
+                    Err(err) => WatchEvent::CompileError(handle, err.to_string()),
+                };
+                let _ = tx.send(event);
             }
         });
-    }
 
-    pub fn get_shaders(&self) -> Arc<Mutex<Option<(ShaderModule, ShaderModule)>>> {
-        self.shader_module.clone()
+        rx
     }
-}
\ No newline at end of file
+}
+
+/// Outcome of a debounced file-watch cycle, sent by the watcher thread so the
+/// application can drain it instead of the thread panicking on error.
+#[derive(Debug)]
+pub enum WatchEvent {
+    /// `handle` was recompiled successfully and now serves the new source.
+    Reloaded(ShaderHandle),
+    /// Recompiling `handle` failed; the previous module is still in place.
+    CompileError(ShaderHandle, String),
+    /// The underlying file watcher reported an error unrelated to any handle.
+    WatchError(Option<PathBuf>, String),
+}